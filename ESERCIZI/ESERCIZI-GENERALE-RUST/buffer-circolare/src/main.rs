@@ -1,7 +1,8 @@
-use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::Duration;
 use rand::Rng;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 struct BufferCircolare<T> {
@@ -23,12 +24,15 @@ impl<T: Clone> BufferCircolare<T> {
         }
     }
 
-    fn insert(&mut self, value: T) {
+    // se il buffer è pieno il valore viene restituito invece di essere perso
+    fn insert(&mut self, value: T) -> Result<(), T> {
         if self.len < self.capacity {
             self.buffer[self.ind_w] = value;
             self.ind_w = (self.ind_w + 1) % self.capacity;
             self.len += 1;
+            return Ok(());
         }
+        Err(value)
     }
 
     fn read(&mut self) -> Option<T> {
@@ -38,38 +42,296 @@ impl<T: Clone> BufferCircolare<T> {
             self.len -= 1;
             return Some(value);
         }
-        return None;
+        None
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum PushError<T> {
+    // buffer pieno (subito per try_push, allo scadere del timeout per push_timeout)
+    Full(T),
+    Closed(T),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum PopError {
+    // buffer vuoto (subito per try_pop, allo scadere del timeout per pop_timeout)
+    Empty,
+    // buffer chiuso e senza più valori da leggere
+    Closed,
+}
+
+impl<T> Display for PushError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PushError::Full(_) => write!(f, "the buffer is full"),
+            PushError::Closed(_) => write!(f, "the buffer is closed"),
+        }
+    }
+}
+
+impl Display for PopError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PopError::Empty => write!(f, "the buffer is empty"),
+            PopError::Closed => write!(f, "the buffer is closed"),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Stato<T> {
+    buffer: BufferCircolare<T>,
+    closed: bool,
+}
+
+// buffer circolare condiviso tra produttori e consumatori: chi trova il buffer
+// pieno (o vuoto) si addormenta sulla condvar invece di ciclare tenendo il lock;
+// dopo close() i consumatori leggono i valori rimasti e poi ricevono Closed
+struct BufferBloccante<T> {
+    stato: Mutex<Stato<T>>,
+    not_full: Condvar,
+    not_empty: Condvar,
+}
+
+impl<T: Clone> BufferBloccante<T> {
+    // una capacità nulla viene portata a 1
+    fn new(capacity: usize, default_value: T) -> Self {
+        Self {
+            stato: Mutex::new(Stato {
+                buffer: BufferCircolare::new(capacity.max(1), default_value),
+                closed: false,
+            }),
+            not_full: Condvar::new(),
+            not_empty: Condvar::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.stato.lock().unwrap().buffer.len
+    }
+
+    fn close(&self) {
+        self.stato.lock().unwrap().closed = true;
+        self.not_full.notify_all();
+        self.not_empty.notify_all();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.stato.lock().unwrap().closed
+    }
+
+    // attende finché c'è spazio o il buffer viene chiuso; senza timeout aspetta per sempre
+    // un timeout che supera il limite di Instant equivale a nessuna scadenza
+    fn wait_push(&self, mut value: T, timeout: Option<Duration>) -> Result<(), PushError<T>> {
+        let deadline = timeout.and_then(|t| Instant::now().checked_add(t));
+        let mut stato = self.stato.lock().unwrap();
+        loop {
+            if stato.closed {
+                return Err(PushError::Closed(value));
+            }
+            match stato.buffer.insert(value) {
+                Ok(()) => {
+                    drop(stato);
+                    self.not_empty.notify_one();
+                    return Ok(());
+                }
+                Err(v) => value = v,
+            }
+            stato = match wait(&self.not_full, stato, deadline) {
+                Some(stato) => stato,
+                None => return Err(PushError::Full(value)),
+            };
+        }
+    }
+
+    fn wait_pop(&self, timeout: Option<Duration>) -> Result<T, PopError> {
+        let deadline = timeout.and_then(|t| Instant::now().checked_add(t));
+        let mut stato = self.stato.lock().unwrap();
+        loop {
+            if let Some(value) = stato.buffer.read() {
+                drop(stato);
+                self.not_full.notify_one();
+                return Ok(value);
+            }
+            if stato.closed {
+                return Err(PopError::Closed);
+            }
+            stato = wait(&self.not_empty, stato, deadline).ok_or(PopError::Empty)?;
+        }
+    }
+
+    fn push(&self, value: T) -> Result<(), PushError<T>> {
+        self.wait_push(value, None)
+    }
+
+    fn push_timeout(&self, value: T, timeout: Duration) -> Result<(), PushError<T>> {
+        self.wait_push(value, Some(timeout))
+    }
+
+    fn try_push(&self, value: T) -> Result<(), PushError<T>> {
+        self.wait_push(value, Some(Duration::ZERO))
+    }
+
+    // None quando il buffer è chiuso e vuoto
+    fn pop(&self) -> Option<T> {
+        self.wait_pop(None).ok()
+    }
+
+    fn pop_timeout(&self, timeout: Duration) -> Result<T, PopError> {
+        self.wait_pop(Some(timeout))
+    }
+
+    fn try_pop(&self) -> Result<T, PopError> {
+        self.wait_pop(Some(Duration::ZERO))
+    }
+}
+
+// un giro di attesa sulla condvar; None se la scadenza è già passata
+fn wait<'a, T>(
+    cvar: &Condvar,
+    guard: MutexGuard<'a, T>,
+    deadline: Option<Instant>,
+) -> Option<MutexGuard<'a, T>> {
+    match deadline {
+        None => Some(cvar.wait(guard).unwrap()),
+        Some(deadline) => {
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            Some(cvar.wait_timeout(guard, deadline - now).unwrap().0)
+        }
     }
 }
 
 fn main() {
-    let buffer = Arc::new(Mutex::new(BufferCircolare::<i32>::new(10, 0)));
+    let buffer = Arc::new(BufferBloccante::<i32>::new(10, 0));
 
     let buffer_clone = buffer.clone();
     let producer = thread::spawn(move || {
-        loop {
-            let mut buf = buffer_clone.lock().unwrap();
-            for _ in 0..buf.capacity {
-                buf.insert(rand::thread_rng().gen_range(10..30));
+        for i in 0..30 {
+            let value = rand::thread_rng().gen_range(10..30);
+            // i primi valori riempiono il buffer, poi si prova senza attendere
+            let result = if i < 10 {
+                buffer_clone.push(value)
+            } else {
+                match buffer_clone.try_push(value) {
+                    Err(PushError::Full(value)) => {
+                        println!("the buffer is full, the producer waits");
+                        buffer_clone.push_timeout(value, Duration::from_secs(5))
+                    }
+                    result => result,
+                }
+            };
+            if let Err(e) = result {
+                println!("producer: {}", e);
+                return;
             }
         }
+        buffer_clone.close();
     });
 
     let buffer_clone = buffer.clone();
     let consumer = thread::spawn(move || {
+        // i primi valori con l'attesa senza limite, poi con un timeout
+        for _ in 0..5 {
+            if let Some(value) = buffer_clone.pop() {
+                println!("{} (still {} in the buffer)", value, buffer_clone.len());
+            }
+        }
         loop {
-            let mut buf = buffer_clone.lock().unwrap();
-            for _ in 0..buf.capacity {
-                buf.read();
-                println!("{:?}", buf);
+            match buffer_clone.pop_timeout(Duration::from_millis(500)) {
+                Ok(value) => println!("{} (still {} in the buffer)", value, buffer_clone.len()),
+                Err(PopError::Empty) => println!("nothing to read yet"),
+                Err(PopError::Closed) => break,
             }
-            drop(buf);
-            thread::sleep(Duration::from_secs(10));
-            println!();
+            thread::sleep(Duration::from_millis(100));
         }
+        println!(
+            "closed: {}, try_pop: {:?}",
+            buffer_clone.is_closed(),
+            buffer_clone.try_pop()
+        );
     });
 
-
     producer.join().unwrap();
     consumer.join().unwrap();
 }
+
+#[cfg(test)]
+mod test {
+    use super::{BufferBloccante, PopError, PushError};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn try_and_timeout_variants() {
+        let buffer = BufferBloccante::new(1, 0);
+        assert_eq!(buffer.try_pop(), Err(PopError::Empty));
+        assert_eq!(buffer.try_push(1), Ok(()));
+        assert_eq!(buffer.try_push(2), Err(PushError::Full(2)));
+        assert_eq!(
+            buffer.push_timeout(2, Duration::from_millis(20)),
+            Err(PushError::Full(2))
+        );
+        assert_eq!(buffer.pop_timeout(Duration::from_millis(20)), Ok(1));
+        assert_eq!(
+            buffer.pop_timeout(Duration::from_millis(20)),
+            Err(PopError::Empty)
+        );
+
+        // un timeout enorme non deve andare in overflow
+        assert_eq!(buffer.push_timeout(3, Duration::MAX), Ok(()));
+        assert_eq!(buffer.pop_timeout(Duration::MAX), Ok(3));
+        buffer.close();
+        assert_eq!(buffer.pop_timeout(Duration::MAX), Err(PopError::Closed));
+    }
+
+    #[test]
+    fn blocking_producer_and_consumer() {
+        let buffer = Arc::new(BufferBloccante::new(2, 0));
+        let producer = {
+            let buffer = buffer.clone();
+            thread::spawn(move || {
+                for i in 1..=100 {
+                    buffer.push(i).unwrap();
+                }
+                buffer.close();
+            })
+        };
+
+        let mut received = Vec::new();
+        while let Some(value) = buffer.pop() {
+            received.push(value);
+        }
+        producer.join().unwrap();
+        assert_eq!(received, (1..=100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn close_wakes_waiters() {
+        let buffer = Arc::new(BufferBloccante::new(1, 0));
+        let consumer = {
+            let buffer = buffer.clone();
+            thread::spawn(move || buffer.pop())
+        };
+        thread::sleep(Duration::from_millis(20));
+        buffer.close();
+        assert_eq!(consumer.join().unwrap(), None);
+
+        let buffer = Arc::new(BufferBloccante::new(1, 0));
+        buffer.push(1).unwrap();
+        let producer = {
+            let buffer = buffer.clone();
+            thread::spawn(move || buffer.push(2))
+        };
+        thread::sleep(Duration::from_millis(20));
+        buffer.close();
+        assert_eq!(producer.join().unwrap(), Err(PushError::Closed(2)));
+        // i valori già inseriti restano leggibili dopo la chiusura
+        assert_eq!(buffer.try_pop(), Ok(1));
+        assert_eq!(buffer.try_pop(), Err(PopError::Closed));
+    }
+}
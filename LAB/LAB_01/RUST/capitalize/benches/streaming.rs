@@ -0,0 +1,62 @@
+// confronto tra la vecchia `conversion` (split su " " e concatenazione con +),
+// la conversione in memoria e quella in streaming; si lancia con `cargo bench`
+use capitalize::{convert, convert_stream, CaseMode};
+use std::hint::black_box;
+use std::io;
+use std::time::{Duration, Instant};
+
+// implementazione originale, tenuta qui solo come riferimento
+#[allow(clippy::assign_op_pattern)]
+fn old_conversion(user: String) -> String {
+    let vec = user.split(" ");
+    let mut res = String::new();
+    for s in vec {
+        res = res + old_capitalize(s).as_str() + " ";
+    }
+    if !res.is_empty() {
+        res.remove(res.len() - 1);
+    }
+    res
+}
+
+fn old_capitalize(s: &str) -> String {
+    let mut str = s.to_string();
+    let len = str.chars().count();
+    if len == 1 {
+        str = str.to_uppercase();
+    } else if len > 1 {
+        str = s[0..1].to_uppercase() + &s[1..];
+    }
+    str
+}
+
+fn bench<F: FnMut()>(name: &str, bytes: usize, mut f: F) {
+    // un giro a vuoto, poi la media su almeno un secondo
+    f();
+    let start = Instant::now();
+    let mut runs = 0;
+    while start.elapsed() < Duration::from_secs(1) {
+        f();
+        runs += 1;
+    }
+    let per_run = start.elapsed() / runs;
+    let mb_s = bytes as f64 / per_run.as_secs_f64() / 1e6;
+    println!("{:<12} {:>10.2?}/run {:>8.1} MB/s", name, per_run, mb_s);
+}
+
+fn main() {
+    // solo parole che iniziano con un carattere ASCII: la vecchia versione
+    // va in panic su iniziali multi-byte
+    let text = "questa è una frase di prova per il benchmark\n".repeat(20_000);
+    println!("input: {} bytes", text.len());
+
+    bench("old", text.len(), || {
+        black_box(old_conversion(black_box(text.clone())));
+    });
+    bench("convert", text.len(), || {
+        black_box(convert(black_box(&text), CaseMode::Title));
+    });
+    bench("stream", text.len(), || {
+        convert_stream(black_box(text.as_bytes()), io::sink(), CaseMode::Title).unwrap();
+    });
+}
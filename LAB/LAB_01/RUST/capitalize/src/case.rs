@@ -0,0 +1,223 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::title::{push_titlecase, TitleCase};
+use crate::words::{identifier_parts, segments, Segment};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaseMode {
+    Title,
+    Sentence,
+    Snake,
+    Kebab,
+    Camel,
+    Pascal,
+    Screaming,
+}
+
+impl CaseMode {
+    pub const ALL: [CaseMode; 7] = [
+        CaseMode::Title,
+        CaseMode::Sentence,
+        CaseMode::Snake,
+        CaseMode::Kebab,
+        CaseMode::Camel,
+        CaseMode::Pascal,
+        CaseMode::Screaming,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CaseMode::Title => "title",
+            CaseMode::Sentence => "sentence",
+            CaseMode::Snake => "snake",
+            CaseMode::Kebab => "kebab",
+            CaseMode::Camel => "camel",
+            CaseMode::Pascal => "pascal",
+            CaseMode::Screaming => "screaming",
+        }
+    }
+}
+
+impl Display for CaseMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for CaseMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CaseMode::ALL
+            .into_iter()
+            .find(|mode| mode.name() == s)
+            .ok_or_else(|| format!("unknown case mode {}", s))
+    }
+}
+
+// converte il testo nella modalità richiesta; title e sentence case lasciano
+// intatti spazi e punteggiatura, le altre modalità producono un identificatore
+// per riga
+pub fn convert(text: &str, mode: CaseMode) -> String {
+    Converter::new(mode).convert(text)
+}
+
+// convertitore che può ricevere il testo un pezzo alla volta: tra una chiamata
+// e l'altra ricorda se la prossima parola è la prima (del testo in title case,
+// della frase in sentence case, della riga negli identificatori)
+#[derive(Clone, Debug)]
+pub struct Converter {
+    mode: CaseMode,
+    title: TitleCase,
+    first: bool,
+}
+
+impl Converter {
+    pub fn new(mode: CaseMode) -> Self {
+        Converter {
+            mode,
+            title: TitleCase::new(),
+            first: true,
+        }
+    }
+
+    // configurazione usata in modalità title (parole minori, "ij" olandese)
+    pub fn title_case(mut self, title: TitleCase) -> Self {
+        self.title = title;
+        self
+    }
+
+    pub fn convert(&mut self, text: &str) -> String {
+        let mut res = String::with_capacity(text.len());
+        self.push(text, false, true, &mut res);
+        res
+    }
+
+    // converte `text` in coda a `res` e restituisce quanti byte ha consumato:
+    // con `hold` si ferma prima dell'ultima parola, che potrebbe continuare
+    // nel pezzo successivo; `end` indica che dopo `text` non arriva altro
+    pub(crate) fn push(&mut self, text: &str, hold: bool, end: bool, res: &mut String) -> usize {
+        let segments = segments(text);
+        let last_word = segments.iter().rposition(|s| matches!(s, Segment::Word(_)));
+        let stop = match last_word {
+            Some(k) if hold => k,
+            _ => segments.len(),
+        };
+
+        let mut consumed = 0;
+        for (k, &segment) in segments[..stop].iter().enumerate() {
+            let last = end && Some(k) == last_word;
+            match segment {
+                Segment::Gap(gap) => {
+                    self.push_gap(gap, res);
+                    consumed += gap.len();
+                }
+                Segment::Word(word) => {
+                    self.push_word(word, last, res);
+                    self.first = false;
+                    consumed += word.len();
+                }
+            }
+        }
+        consumed
+    }
+
+    fn push_gap(&mut self, gap: &str, res: &mut String) {
+        match self.mode {
+            CaseMode::Title => res.push_str(gap),
+            CaseMode::Sentence => {
+                res.push_str(gap);
+                if gap.contains(['.', '!', '?']) {
+                    self.first = true;
+                }
+            }
+            _ => {
+                for _ in gap.matches('\n') {
+                    res.push('\n');
+                    self.first = true;
+                }
+            }
+        }
+    }
+
+    fn push_word(&mut self, word: &str, last: bool, res: &mut String) {
+        let separator = match self.mode {
+            CaseMode::Title => return self.title.push_word(word, self.first, last, res),
+            CaseMode::Sentence if self.first => return capitalized(word, res),
+            CaseMode::Sentence => return lower(word, res),
+            CaseMode::Snake | CaseMode::Screaming => "_",
+            CaseMode::Kebab => "-",
+            CaseMode::Camel | CaseMode::Pascal => "",
+        };
+
+        for part in identifier_parts(word) {
+            if !self.first {
+                res.push_str(separator);
+            }
+            match self.mode {
+                CaseMode::Screaming => res.push_str(&part.to_uppercase()),
+                CaseMode::Camel if self.first => lower(&part, res),
+                CaseMode::Camel | CaseMode::Pascal => capitalized(&part, res),
+                _ => lower(&part, res),
+            }
+            self.first = false;
+        }
+    }
+}
+
+fn lower(part: &str, res: &mut String) {
+    res.push_str(&part.to_lowercase());
+}
+
+fn capitalized(part: &str, res: &mut String) {
+    let part = part.to_lowercase();
+    let mut chars = part.chars();
+    if let Some(first) = chars.next() {
+        push_titlecase(first, res);
+        res.push_str(chars.as_str());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{convert, CaseMode};
+
+    #[test]
+    fn all_modes() {
+        let text = "l'amico  di parseHTTPRequest, è qui";
+        let expected = [
+            (CaseMode::Title, "L'Amico  Di ParseHTTPRequest, È Qui"),
+            (CaseMode::Sentence, "L'amico  di parsehttprequest, è qui"),
+            (CaseMode::Snake, "lamico_di_parse_http_request_è_qui"),
+            (CaseMode::Kebab, "lamico-di-parse-http-request-è-qui"),
+            (CaseMode::Camel, "lamicoDiParseHttpRequestÈQui"),
+            (CaseMode::Pascal, "LamicoDiParseHttpRequestÈQui"),
+            (CaseMode::Screaming, "LAMICO_DI_PARSE_HTTP_REQUEST_È_QUI"),
+        ];
+        for (mode, result) in expected {
+            assert_eq!(convert(text, mode), result, "{}", mode);
+        }
+    }
+
+    #[test]
+    fn sentences_and_lines() {
+        assert_eq!(
+            convert("CIAO. come stai? BENE!\nsì", CaseMode::Sentence),
+            "Ciao. Come stai? Bene!\nSì"
+        );
+        assert_eq!(
+            convert("first line\n\nstraße due\n", CaseMode::Pascal),
+            "FirstLine\n\nStraßeDue\n"
+        );
+        assert_eq!(convert("", CaseMode::Snake), "");
+    }
+
+    #[test]
+    fn parse_mode() {
+        for mode in CaseMode::ALL {
+            assert_eq!(mode.name().parse(), Ok(mode));
+        }
+        assert!("upper".parse::<CaseMode>().is_err());
+    }
+}
@@ -1,9 +1,17 @@
-mod main;
+mod case;
+pub mod main_conv;
+mod stream;
+mod title;
+mod words;
+
+pub use case::{convert, CaseMode, Converter};
+pub use stream::convert_stream;
+pub use title::{title_case, TitleCase, ENGLISH_SMALL_WORDS, ITALIAN_SMALL_WORDS};
 
 #[cfg(test)]
 mod test {
 
-    use crate::main::main_conv::conversion;
+    use crate::main_conv::conversion;
 
     #[test]
     fn test_1() {
@@ -1,55 +1,32 @@
-use main_conv::conversion;
+use capitalize::{convert_stream, CaseMode};
+use std::env;
+use std::io::{self, BufWriter};
 
-fn main() {
-    let user = "questa è una frase".to_string();
-    conversion(user);
-}
-
-pub mod main_conv {
-    pub fn conversion(user: String) -> String {
-        // questo per interagire con l'utente da console
-        //std::io::stdout().write("Valore: ".as_bytes()).unwrap();
-        /*print!("Valore: ");
-        stdout().flush().unwrap();
+const USAGE: &str =
+    "usage: capitalize [--title | --sentence | --snake | --kebab | --camel | --pascal | --screaming]";
 
-        let stdin = io::stdin();
-        match stdin.read_line(&mut user) {
-            Ok(..) => {
-                print!("valore catturato: {user}");
+// legge il testo da stdin e lo scrive convertito su stdout (default: title case)
+fn main() {
+    let mut mode = CaseMode::Title;
+    for arg in env::args().skip(1) {
+        match arg.strip_prefix("--").map(str::parse) {
+            Some(Ok(m)) => mode = m,
+            _ if arg == "-h" || arg == "--help" => {
+                println!("{}", USAGE);
+                return;
             }
-            Err(e) => {
-                println!("Error: {e}");
+            _ => {
+                eprintln!("unknown option {}", arg);
+                eprintln!("{}", USAGE);
+                std::process::exit(2);
             }
-        }*/
-
-        println!("\nValore catturato: {user}");
-
-        let vec = user.split(" ");
-        let mut res = String::new();
-        for s in vec {
-            res = res + capitalize(s).as_str() + " ";
         }
-        if !res.is_empty() {
-            res.remove(res.len() - 1);
-        }
-
-        println!("Valore aggiornato: {res}");
-
-        return res;
     }
 
-    fn capitalize(s: &str) -> String {
-        let mut str = s.to_string();
-        let len = str.chars().count();
-
-        if len == 1 {
-            str = str.to_uppercase();
-        } else if len > 1 {
-            str = (&s[0..1]).parse().unwrap();
-            str = str.to_uppercase();
-            str = str + &s[1..];
-        }
-
-        return str;
+    // il testo viene convertito mentre arriva, senza leggerlo tutto in memoria
+    let stdout = io::stdout();
+    if let Err(e) = convert_stream(io::stdin().lock(), BufWriter::new(stdout.lock()), mode) {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
     }
 }
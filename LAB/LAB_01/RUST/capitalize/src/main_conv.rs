@@ -0,0 +1,6 @@
+use crate::title_case;
+
+// maiuscola all'inizio di ogni parola, spazi e separatori restano invariati
+pub fn conversion(user: String) -> String {
+    title_case(&user)
+}
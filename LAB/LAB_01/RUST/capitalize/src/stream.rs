@@ -0,0 +1,139 @@
+use std::io::{self, ErrorKind, Read, Write};
+
+use crate::{CaseMode, Converter};
+
+// dimensione delle letture da `input`
+const CHUNK: usize = 8 * 1024;
+// oltre questa lunghezza una parola viene spezzata pur di non crescere in memoria
+const MAX_HOLD: usize = 64 * 1024;
+
+fn invalid_utf8(e: std::str::Utf8Error) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, e)
+}
+
+impl Converter {
+    // converte tutto `input` scrivendo su `output` un pezzo alla volta: in
+    // memoria restano al più un blocco letto e l'ultima parola incompleta
+    pub fn convert_stream<R: Read, W: Write>(
+        &mut self,
+        mut input: R,
+        mut output: W,
+    ) -> io::Result<()> {
+        let mut chunk = [0; CHUNK];
+        let mut buf = Vec::with_capacity(2 * CHUNK);
+        let mut res = String::with_capacity(2 * CHUNK);
+
+        loop {
+            let n = match input.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            buf.extend_from_slice(&chunk[..n]);
+
+            // un carattere spezzato a fine blocco viene completato dalla lettura successiva
+            let text = match std::str::from_utf8(&buf) {
+                Ok(text) => text,
+                Err(e) if e.error_len().is_none() => {
+                    std::str::from_utf8(&buf[..e.valid_up_to()]).map_err(invalid_utf8)?
+                }
+                Err(e) => return Err(invalid_utf8(e)),
+            };
+
+            res.clear();
+            let mut consumed = self.push(text, true, false, &mut res);
+            if consumed == 0 && text.len() > MAX_HOLD {
+                consumed = self.push(text, false, false, &mut res);
+            }
+            output.write_all(res.as_bytes())?;
+            buf.drain(..consumed);
+        }
+
+        let text = std::str::from_utf8(&buf).map_err(invalid_utf8)?;
+        res.clear();
+        self.push(text, false, true, &mut res);
+        output.write_all(res.as_bytes())?;
+        output.flush()
+    }
+}
+
+pub fn convert_stream<R: Read, W: Write>(input: R, output: W, mode: CaseMode) -> io::Result<()> {
+    Converter::new(mode).convert_stream(input, output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::convert_stream;
+    use crate::{convert, CaseMode, Converter, TitleCase, ITALIAN_SMALL_WORDS};
+    use std::io::{ErrorKind, Read};
+
+    // reader che restituisce pochi byte alla volta, per spezzare parole e caratteri
+    struct Drip<'a> {
+        data: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Drip<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.step = self.step % 3 + 1;
+            let n = self.step.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn same_result_as_convert() {
+        let text = "l'amico  dell'uomo è qui. ßtraße\tparseHTTP e\u{301}poca!\ndi là e di\n";
+        for mode in CaseMode::ALL {
+            let mut out = Vec::new();
+            let input = Drip {
+                data: text.as_bytes(),
+                step: 0,
+            };
+            convert_stream(input, &mut out, mode).unwrap();
+            assert_eq!(
+                String::from_utf8(out).unwrap(),
+                convert(text, mode),
+                "{}",
+                mode
+            );
+        }
+
+        let title = TitleCase::new().small_words(ITALIAN_SMALL_WORDS);
+        let mut out = Vec::new();
+        Converter::new(CaseMode::Title)
+            .title_case(title.clone())
+            .convert_stream(
+                Drip {
+                    data: text.as_bytes(),
+                    step: 0,
+                },
+                &mut out,
+            )
+            .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), title.convert(text));
+    }
+
+    #[test]
+    fn long_input() {
+        let text = "una parola dopo l'altra ".repeat(10_000) + &"x".repeat(100_000);
+        let mut out = Vec::new();
+        convert_stream(text.as_bytes(), &mut out, CaseMode::Title).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.len(), text.len());
+        assert!(out.starts_with("Una Parola Dopo L'Altra Una"));
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut out = Vec::new();
+        let err = convert_stream(&b"ciao \xff mondo"[..], &mut out, CaseMode::Title).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        // sequenza troncata a fine input
+        let err = convert_stream(&b"ciao \xc3"[..], &mut out, CaseMode::Title).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
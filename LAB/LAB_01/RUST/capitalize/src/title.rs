@@ -0,0 +1,190 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::words::{is_apostrophe, segments, Segment};
+
+pub const ITALIAN_SMALL_WORDS: &[&str] = &[
+    "a", "ad", "al", "con", "da", "dal", "de", "dei", "del", "della", "di", "e", "ed", "fra",
+    "gli", "i", "il", "in", "la", "le", "lo", "nel", "nella", "o", "per", "su", "sul", "tra", "un",
+    "una", "uno",
+];
+
+pub const ENGLISH_SMALL_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "in", "nor", "of", "on", "or", "the", "to",
+    "up", "via",
+];
+
+// title case configurabile: le parole in `small_words` restano minuscole
+// tranne quando sono la prima o l'ultima del testo
+#[derive(Clone, Debug, Default)]
+pub struct TitleCase {
+    small_words: Vec<String>,
+    dutch_ij: bool,
+}
+
+impl TitleCase {
+    pub fn new() -> Self {
+        TitleCase::default()
+    }
+
+    pub fn small_words<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.small_words
+            .extend(words.into_iter().map(|w| w.as_ref().to_lowercase()));
+        self
+    }
+
+    // in olandese il digramma "ij" a inizio parola diventa "IJ" ("ijsselmeer" -> "IJsselmeer")
+    pub fn dutch_ij(mut self, enabled: bool) -> Self {
+        self.dutch_ij = enabled;
+        self
+    }
+
+    pub fn convert(&self, text: &str) -> String {
+        let segments = segments(text);
+        let last = segments.iter().rposition(|s| matches!(s, Segment::Word(_)));
+
+        let mut res = String::with_capacity(text.len());
+        let mut first = true;
+        for (k, segment) in segments.iter().enumerate() {
+            match *segment {
+                Segment::Gap(gap) => res.push_str(gap),
+                Segment::Word(word) => {
+                    self.push_word(word, first, Some(k) == last, &mut res);
+                    first = false;
+                }
+            }
+        }
+        res
+    }
+
+    pub(crate) fn push_word(&self, word: &str, first: bool, last: bool, res: &mut String) {
+        if !first
+            && !last
+            && !self.small_words.is_empty()
+            && self.small_words.contains(&word.to_lowercase())
+        {
+            res.push_str(&word.to_lowercase());
+        } else {
+            self.capitalize_word(word, res);
+        }
+    }
+
+    // dopo un apostrofo si ricomincia con la maiuscola ("L'Amico", "O'Neill")
+    // salvo le terminazioni brevi delle forme contratte ("Don't", "It's", "We're")
+    fn capitalize_word(&self, word: &str, res: &mut String) {
+        let mut start = 0;
+        if !word.contains(['\'', '\u{2019}']) {
+            return self.capitalize_part(word, res);
+        }
+        for (i, g) in word.grapheme_indices(true) {
+            if is_apostrophe(g) {
+                let rest = &word[i + g.len()..];
+                if rest.graphemes(true).take(3).count() > 2 {
+                    self.capitalize_part(&word[start..i], res);
+                    res.push_str(g);
+                    start = i + g.len();
+                }
+            }
+        }
+        self.capitalize_part(&word[start..], res);
+    }
+
+    // maiuscola sul primo grafema, il resto resta invariato
+    fn capitalize_part(&self, part: &str, res: &mut String) {
+        if self.dutch_ij {
+            let mut chars = part.chars();
+            if matches!(chars.next(), Some('i' | 'I')) && matches!(chars.next(), Some('j' | 'J')) {
+                res.push_str("IJ");
+                res.push_str(chars.as_str());
+                return;
+            }
+        }
+
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            push_titlecase(first, res);
+            res.push_str(chars.as_str());
+        }
+    }
+}
+
+// la maiuscola "da titolo" non sempre coincide con to_uppercase: i digrammi
+// come ǆ hanno una forma apposita (ǅ) e ß e le legature diventano due lettere
+pub(crate) fn push_titlecase(c: char, res: &mut String) {
+    match c {
+        'ß' => res.push_str("Ss"),
+        'Ǆ' | 'ǅ' | 'ǆ' => res.push('ǅ'),
+        'Ǉ' | 'ǈ' | 'ǉ' => res.push('ǈ'),
+        'Ǌ' | 'ǋ' | 'ǌ' => res.push('ǋ'),
+        'Ǳ' | 'ǲ' | 'ǳ' => res.push('ǲ'),
+        'ﬀ' => res.push_str("Ff"),
+        'ﬁ' => res.push_str("Fi"),
+        'ﬂ' => res.push_str("Fl"),
+        'ﬃ' => res.push_str("Ffi"),
+        'ﬄ' => res.push_str("Ffl"),
+        'ﬅ' | 'ﬆ' => res.push_str("St"),
+        _ => res.extend(c.to_uppercase()),
+    }
+}
+
+// title case senza eccezioni
+pub fn title_case(text: &str) -> String {
+    TitleCase::new().convert(text)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{title_case, TitleCase, ENGLISH_SMALL_WORDS, ITALIAN_SMALL_WORDS};
+
+    #[test]
+    fn whitespace_and_separators() {
+        assert_eq!(
+            title_case(" questa\tè  una\nfrase "),
+            " Questa\tÈ  Una\nFrase "
+        );
+        assert_eq!(title_case("ben-essere"), "Ben-Essere");
+        assert_eq!(title_case("l'amico dell'uomo"), "L'Amico Dell'Uomo");
+        assert_eq!(title_case("don't o'neill"), "Don't O'Neill");
+        assert_eq!(title_case("ñandú, ægir ωμέγα"), "Ñandú, Ægir Ωμέγα");
+    }
+
+    #[test]
+    fn special_casing() {
+        // e + accento combinante: il primo grafema è composto da due char
+        assert_eq!(title_case("e\u{301}poca"), "E\u{301}poca");
+        assert_eq!(title_case("ßtraße"), "Sstraße");
+        assert_eq!(title_case("ǆungla ǌoj"), "ǅungla ǋoj");
+        assert_eq!(title_case("ﬁne"), "Fine");
+        assert_eq!(title_case("ijsselmeer"), "Ijsselmeer");
+        assert_eq!(
+            TitleCase::new().dutch_ij(true).convert("het ijsselmeer"),
+            "Het IJsselmeer"
+        );
+    }
+
+    #[test]
+    fn small_words() {
+        let italian = TitleCase::new().small_words(ITALIAN_SMALL_WORDS);
+        assert_eq!(
+            italian.convert("il nome della rosa e di altri"),
+            "Il Nome della Rosa e di Altri"
+        );
+        // la prima e l'ultima parola sono sempre maiuscole
+        assert_eq!(italian.convert("di qua e di"), "Di Qua e Di");
+
+        let english = TitleCase::new().small_words(ENGLISH_SMALL_WORDS);
+        assert_eq!(
+            english.convert("THE LORD OF THE RINGS"),
+            "THE LORD of the RINGS"
+        );
+        assert_eq!(
+            TitleCase::new()
+                .small_words(["Of"])
+                .convert("war of worlds"),
+            "War of Worlds"
+        );
+    }
+}
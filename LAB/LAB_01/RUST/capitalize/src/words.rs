@@ -0,0 +1,115 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+// pezzo di testo: una parola oppure quello che sta tra due parole
+// (spazi, tab, trattini, punteggiatura), che va riportato così com'è
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Segment<'a> {
+    Word(&'a str),
+    Gap(&'a str),
+}
+
+pub(crate) fn is_apostrophe(grapheme: &str) -> bool {
+    grapheme == "'" || grapheme == "\u{2019}"
+}
+
+fn is_word(grapheme: &str) -> bool {
+    grapheme.chars().next().is_some_and(char::is_alphanumeric)
+}
+
+// divide il testo in parole e separatori lavorando sui grafemi, così un
+// accento combinante resta attaccato alla sua lettera; un apostrofo tra due
+// lettere fa parte della parola ("l'amico", "don't")
+pub(crate) fn segments(text: &str) -> Vec<Segment<'_>> {
+    let mut graphemes = text.grapheme_indices(true).peekable();
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut in_word = false;
+
+    while let Some((i, g)) = graphemes.next() {
+        let word = is_word(g)
+            || (in_word
+                && is_apostrophe(g)
+                && graphemes.peek().is_some_and(|&(_, next)| is_word(next)));
+        if word != in_word && i > start {
+            segments.push(if in_word {
+                Segment::Word(&text[start..i])
+            } else {
+                Segment::Gap(&text[start..i])
+            });
+            start = i;
+        }
+        in_word = word;
+    }
+
+    if start < text.len() {
+        segments.push(if in_word {
+            Segment::Word(&text[start..])
+        } else {
+            Segment::Gap(&text[start..])
+        });
+    }
+    segments
+}
+
+// spezza una parola nei punti in cui cambia la capitalizzazione, come negli
+// identificatori ("parseHTTPRequest" -> parse, HTTP, Request); gli apostrofi
+// vengono tolti
+pub(crate) fn identifier_parts(word: &str) -> Vec<String> {
+    let graphemes: Vec<&str> = word.graphemes(true).filter(|g| !is_apostrophe(g)).collect();
+    let upper = |g: &str| g.chars().next().is_some_and(char::is_uppercase);
+    let lower = |g: &str| g.chars().next().is_some_and(|c| !c.is_uppercase());
+
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    for (k, &g) in graphemes.iter().enumerate() {
+        let boundary = k > 0
+            && upper(g)
+            && (lower(graphemes[k - 1])
+                || graphemes.get(k + 1).is_some_and(|next| lower(next)) && upper(graphemes[k - 1]));
+        if boundary {
+            parts.push(std::mem::take(&mut part));
+        }
+        part.push_str(g);
+    }
+    if !part.is_empty() {
+        parts.push(part);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod test {
+    use super::{identifier_parts, segments, Segment};
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(
+            identifier_parts("parseHTTPRequest2"),
+            vec!["parse", "HTTP", "Request2"]
+        );
+        assert_eq!(identifier_parts("don't"), vec!["dont"]);
+        assert_eq!(identifier_parts("ÈUnaFrase"), vec!["È", "Una", "Frase"]);
+        assert_eq!(identifier_parts("ABC"), vec!["ABC"]);
+    }
+
+    #[test]
+    fn words_and_gaps() {
+        assert_eq!(
+            segments("  l'amico\tben-essere, don't'"),
+            vec![
+                Segment::Gap("  "),
+                Segment::Word("l'amico"),
+                Segment::Gap("\t"),
+                Segment::Word("ben"),
+                Segment::Gap("-"),
+                Segment::Word("essere"),
+                Segment::Gap(", "),
+                Segment::Word("don't"),
+                Segment::Gap("'"),
+            ]
+        );
+        assert_eq!(segments(""), vec![]);
+        // e + accento combinante è un solo grafema
+        assert_eq!(segments("e\u{301}"), vec![Segment::Word("e\u{301}")]);
+    }
+}
@@ -0,0 +1,199 @@
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+
+use crate::checksum::digits;
+use crate::{ChecksumAlgorithm, ChecksumError, Luhn};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CardIssuer {
+    Visa,
+    Mastercard,
+    AmericanExpress,
+    Discover,
+    DinersClub,
+    Jcb,
+    UnionPay,
+    Maestro,
+    Unknown,
+}
+
+impl Display for CardIssuer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            CardIssuer::Visa => "Visa",
+            CardIssuer::Mastercard => "Mastercard",
+            CardIssuer::AmericanExpress => "American Express",
+            CardIssuer::Discover => "Discover",
+            CardIssuer::DinersClub => "Diners Club",
+            CardIssuer::Jcb => "JCB",
+            CardIssuer::UnionPay => "UnionPay",
+            CardIssuer::Maestro => "Maestro",
+            CardIssuer::Unknown => "unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CardInfo {
+    pub issuer: CardIssuer,
+    // solo le cifre, senza spazi
+    pub number: String,
+    pub luhn_valid: bool,
+    // la lunghezza è tra quelle ammesse dal circuito (sempre false se sconosciuto)
+    pub length_valid: bool,
+}
+
+impl CardInfo {
+    pub fn is_valid(&self) -> bool {
+        self.luhn_valid && self.length_valid
+    }
+}
+
+// (circuito, intervallo di prefissi, lunghezze ammesse); i prefissi più
+// specifici vanno prima di quelli più generici che li contengono
+const ISSUERS: &[(CardIssuer, RangeInclusive<u32>, RangeInclusive<usize>)] = &[
+    (CardIssuer::AmericanExpress, 34..=34, 15..=15),
+    (CardIssuer::AmericanExpress, 37..=37, 15..=15),
+    (CardIssuer::DinersClub, 300..=305, 14..=19),
+    (CardIssuer::DinersClub, 36..=36, 14..=19),
+    (CardIssuer::DinersClub, 38..=39, 14..=19),
+    (CardIssuer::Jcb, 3528..=3589, 16..=19),
+    (CardIssuer::Visa, 4..=4, 13..=19),
+    (CardIssuer::Maestro, 5018..=5018, 12..=19),
+    (CardIssuer::Maestro, 5020..=5020, 12..=19),
+    (CardIssuer::Maestro, 5038..=5038, 12..=19),
+    (CardIssuer::Maestro, 5893..=5893, 12..=19),
+    (CardIssuer::Maestro, 6304..=6304, 12..=19),
+    (CardIssuer::Maestro, 6759..=6759, 12..=19),
+    (CardIssuer::Maestro, 6761..=6763, 12..=19),
+    (CardIssuer::Mastercard, 51..=55, 16..=16),
+    (CardIssuer::Mastercard, 2221..=2720, 16..=16),
+    (CardIssuer::Discover, 6011..=6011, 16..=19),
+    (CardIssuer::Discover, 622126..=622925, 16..=19),
+    (CardIssuer::Discover, 644..=649, 16..=19),
+    (CardIssuer::Discover, 65..=65, 16..=19),
+    (CardIssuer::UnionPay, 62..=62, 16..=19),
+];
+
+// Visa accetta solo 13, 16 e 19 cifre
+fn visa_length(len: usize) -> bool {
+    matches!(len, 13 | 16 | 19)
+}
+
+// cifre del numero senza spazi, errore al primo carattere che non è una cifra
+fn card_digits(number: &str) -> Result<String, ChecksumError> {
+    Ok(digits(number, &[' '])?
+        .into_iter()
+        .map(|digit| char::from_digit(digit, 10).unwrap())
+        .collect())
+}
+
+// aggiunge in fondo a un numero parziale la cifra di controllo di Luhn
+pub fn append_check_digit(partial: &str) -> Result<String, ChecksumError> {
+    let digits = card_digits(partial)?;
+    Ok(format!("{}{}", digits, Luhn.compute_check_digit(&digits)?))
+}
+
+pub fn card_info(number: &str) -> Result<CardInfo, ChecksumError> {
+    let number = card_digits(number)?;
+    if number.is_empty() {
+        return Err(ChecksumError::InvalidLength(0));
+    }
+
+    let len = number.len();
+    let (issuer, length_valid) = ISSUERS
+        .iter()
+        .find(|(_, prefixes, _)| {
+            let digits = prefixes.start().to_string().len();
+            number
+                .get(..digits)
+                .and_then(|prefix| prefix.parse().ok())
+                .is_some_and(|prefix| prefixes.contains(&prefix))
+        })
+        .map(|(issuer, _, lengths)| {
+            let valid = match issuer {
+                CardIssuer::Visa => visa_length(len),
+                _ => lengths.contains(&len),
+            };
+            (*issuer, valid)
+        })
+        .unwrap_or((CardIssuer::Unknown, false));
+
+    Ok(CardInfo {
+        issuer,
+        luhn_valid: Luhn.validate(&number),
+        length_valid,
+        number,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{append_check_digit, card_info, CardIssuer};
+    use crate::ChecksumError;
+
+    #[test]
+    fn issuers() {
+        let cases = [
+            ("4539 3195 0343 6467", CardIssuer::Visa),
+            ("5555 5555 5555 4444", CardIssuer::Mastercard),
+            ("2223 0031 2200 3222", CardIssuer::Mastercard),
+            ("3782 822463 10005", CardIssuer::AmericanExpress),
+            ("6011 1111 1111 1117", CardIssuer::Discover),
+            ("3056 9309 0259 04", CardIssuer::DinersClub),
+            ("3530 1113 3330 0000", CardIssuer::Jcb),
+            ("6200 0000 0000 0005", CardIssuer::UnionPay),
+            ("6759 6498 2643 8453", CardIssuer::Maestro),
+        ];
+        for (number, issuer) in cases {
+            let info = card_info(number).unwrap();
+            assert_eq!(info.issuer, issuer, "{}", number);
+            assert!(info.is_valid(), "{}", number);
+        }
+    }
+
+    #[test]
+    fn wrong_length_or_checksum() {
+        let info = card_info("4539 3195 0343 646").unwrap();
+        assert_eq!(info.issuer, CardIssuer::Visa);
+        assert!(!info.length_valid);
+
+        let info = card_info("4539 3195 0343 6468").unwrap();
+        assert!(info.length_valid && !info.luhn_valid);
+
+        let info = card_info("9999 9999 9999 9995").unwrap();
+        assert_eq!(info.issuer, CardIssuer::Unknown);
+        assert!(!info.is_valid());
+    }
+
+    #[test]
+    fn invalid_characters_are_reported() {
+        assert_eq!(
+            card_info("4539-3195"),
+            Err(ChecksumError::InvalidCharacter {
+                ch: '-',
+                position: 4
+            })
+        );
+        assert_eq!(
+            append_check_digit("4539 319①"),
+            Err(ChecksumError::InvalidCharacter {
+                ch: '①',
+                position: 8
+            })
+        );
+    }
+
+    #[test]
+    fn check_digit() {
+        assert_eq!(
+            append_check_digit("4539 3195 0343 646"),
+            Ok("4539319503436467".to_string())
+        );
+        assert_eq!(append_check_digit(""), Err(ChecksumError::InvalidLength(0)));
+        assert!(card_info(&append_check_digit("555555555555444").unwrap())
+            .unwrap()
+            .is_valid());
+    }
+}
@@ -0,0 +1,444 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChecksumError {
+    // carattere non ammesso, con la sua posizione (in caratteri) nella stringa originale
+    InvalidCharacter { ch: char, position: usize },
+    // numero di cifre/caratteri significativi non valido per l'algoritmo
+    InvalidLength(usize),
+}
+
+impl Display for ChecksumError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChecksumError::InvalidCharacter { ch, position } => {
+                write!(f, "invalid character {:?} at position {}", ch, position)
+            }
+            ChecksumError::InvalidLength(len) => write!(f, "invalid length {}", len),
+        }
+    }
+}
+
+impl std::error::Error for ChecksumError {}
+
+pub trait ChecksumAlgorithm {
+    fn name(&self) -> &'static str;
+
+    // true se il codice completo (cifra di controllo inclusa) è corretto
+    fn validate(&self, code: &str) -> bool;
+
+    // cifra (o cifre, per l'IBAN) di controllo da aggiungere a `payload`
+    fn compute_check_digit(&self, payload: &str) -> Result<String, ChecksumError>;
+
+    // `payload` completato con la cifra di controllo
+    fn complete(&self, payload: &str) -> Result<String, ChecksumError> {
+        let check = self.compute_check_digit(payload)?;
+        Ok(format!("{}{}", payload, check))
+    }
+}
+
+pub struct Luhn;
+pub struct Verhoeff;
+pub struct Damm;
+pub struct Isbn10;
+pub struct Isbn13;
+pub struct Iban;
+
+// estrae le cifre ignorando i caratteri in `skip`, errore per qualsiasi altro carattere
+pub(crate) fn digits(code: &str, skip: &[char]) -> Result<Vec<u32>, ChecksumError> {
+    code.chars()
+        .enumerate()
+        .filter(|(_, ch)| !skip.contains(ch))
+        .map(|(position, ch)| match ch {
+            '0'..='9' => Ok(ch as u32 - '0' as u32),
+            _ => Err(ChecksumError::InvalidCharacter { ch, position }),
+        })
+        .collect()
+}
+
+fn digit_char(digit: u32) -> String {
+    char::from_digit(digit, 10).unwrap().to_string()
+}
+
+// somma di Luhn: raddoppia una cifra sì e una no partendo da destra,
+// dalla prima se `double_first` è vero
+fn luhn_sum(digits: &[u32], double_first: bool) -> u32 {
+    digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| {
+            if (i % 2 == 0) == double_first {
+                let doubled = digit * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                digit
+            }
+        })
+        .sum()
+}
+
+impl ChecksumAlgorithm for Luhn {
+    fn name(&self) -> &'static str {
+        "luhn"
+    }
+
+    fn validate(&self, code: &str) -> bool {
+        match digits(code, &[' ']) {
+            Ok(digits) if digits.len() > 1 => luhn_sum(&digits, false).is_multiple_of(10),
+            _ => false,
+        }
+    }
+
+    fn compute_check_digit(&self, payload: &str) -> Result<String, ChecksumError> {
+        let digits = digits(payload, &[' '])?;
+        if digits.is_empty() {
+            return Err(ChecksumError::InvalidLength(0));
+        }
+        Ok(digit_char((10 - luhn_sum(&digits, true) % 10) % 10))
+    }
+}
+
+const VERHOEFF_D: [[usize; 10]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+    [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+    [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+    [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+    [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+    [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+    [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+    [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+    [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+];
+
+const VERHOEFF_P: [[usize; 10]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+    [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+    [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+    [9, 4, 5, 3, 1, 2, 6, 8, 7, 0],
+    [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+    [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+    [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+];
+
+const VERHOEFF_INV: [u32; 10] = [0, 4, 3, 2, 1, 5, 6, 7, 8, 9];
+
+// `offset` è 0 per validare e 1 per calcolare la cifra mancante in fondo
+fn verhoeff(digits: &[u32], offset: usize) -> usize {
+    digits.iter().rev().enumerate().fold(0, |c, (i, &digit)| {
+        VERHOEFF_D[c][VERHOEFF_P[(i + offset) % 8][digit as usize]]
+    })
+}
+
+impl ChecksumAlgorithm for Verhoeff {
+    fn name(&self) -> &'static str {
+        "verhoeff"
+    }
+
+    fn validate(&self, code: &str) -> bool {
+        match digits(code, &[' ']) {
+            Ok(digits) if digits.len() > 1 => verhoeff(&digits, 0) == 0,
+            _ => false,
+        }
+    }
+
+    fn compute_check_digit(&self, payload: &str) -> Result<String, ChecksumError> {
+        let digits = digits(payload, &[' '])?;
+        if digits.is_empty() {
+            return Err(ChecksumError::InvalidLength(0));
+        }
+        Ok(digit_char(VERHOEFF_INV[verhoeff(&digits, 1)]))
+    }
+}
+
+const DAMM: [[u32; 10]; 10] = [
+    [0, 3, 1, 7, 5, 9, 8, 6, 4, 2],
+    [7, 0, 9, 2, 1, 5, 4, 8, 6, 3],
+    [4, 2, 0, 6, 8, 7, 1, 3, 5, 9],
+    [1, 7, 5, 0, 9, 8, 3, 4, 2, 6],
+    [6, 1, 2, 3, 0, 4, 5, 9, 7, 8],
+    [3, 6, 7, 4, 2, 0, 9, 5, 8, 1],
+    [5, 8, 6, 9, 7, 2, 0, 1, 3, 4],
+    [8, 9, 4, 5, 3, 6, 2, 0, 1, 7],
+    [9, 4, 3, 8, 6, 1, 7, 2, 0, 5],
+    [2, 5, 8, 1, 4, 3, 6, 7, 9, 0],
+];
+
+fn damm(digits: &[u32]) -> u32 {
+    digits
+        .iter()
+        .fold(0, |interim, &digit| DAMM[interim as usize][digit as usize])
+}
+
+impl ChecksumAlgorithm for Damm {
+    fn name(&self) -> &'static str {
+        "damm"
+    }
+
+    fn validate(&self, code: &str) -> bool {
+        match digits(code, &[' ']) {
+            Ok(digits) if digits.len() > 1 => damm(&digits) == 0,
+            _ => false,
+        }
+    }
+
+    fn compute_check_digit(&self, payload: &str) -> Result<String, ChecksumError> {
+        let digits = digits(payload, &[' '])?;
+        if digits.is_empty() {
+            return Err(ChecksumError::InvalidLength(0));
+        }
+        Ok(digit_char(damm(&digits)))
+    }
+}
+
+// pesi 10, 9, ..., 2 sulle prime nove cifre
+fn isbn10_sum(digits: &[u32]) -> u32 {
+    digits
+        .iter()
+        .zip((2..=10).rev())
+        .map(|(digit, weight)| digit * weight)
+        .sum()
+}
+
+impl ChecksumAlgorithm for Isbn10 {
+    fn name(&self) -> &'static str {
+        "isbn10"
+    }
+
+    // la cifra di controllo può essere 'X' (vale 10)
+    fn validate(&self, code: &str) -> bool {
+        let code = code.trim_end();
+        let (payload, check) = match code.char_indices().last() {
+            Some((index, ch)) => (&code[..index], ch),
+            None => return false,
+        };
+        let check = match check {
+            'X' | 'x' => 10,
+            '0'..='9' => check as u32 - '0' as u32,
+            _ => return false,
+        };
+
+        match digits(payload, &[' ', '-']) {
+            Ok(digits) if digits.len() == 9 => (isbn10_sum(&digits) + check).is_multiple_of(11),
+            _ => false,
+        }
+    }
+
+    fn compute_check_digit(&self, payload: &str) -> Result<String, ChecksumError> {
+        let digits = digits(payload, &[' ', '-'])?;
+        if digits.len() != 9 {
+            return Err(ChecksumError::InvalidLength(digits.len()));
+        }
+        Ok(match (11 - isbn10_sum(&digits) % 11) % 11 {
+            10 => "X".to_string(),
+            check => digit_char(check),
+        })
+    }
+}
+
+// pesi alternati 1 e 3 da sinistra
+fn isbn13_sum(digits: &[u32]) -> u32 {
+    digits
+        .iter()
+        .enumerate()
+        .map(|(i, digit)| if i % 2 == 0 { *digit } else { digit * 3 })
+        .sum()
+}
+
+impl ChecksumAlgorithm for Isbn13 {
+    fn name(&self) -> &'static str {
+        "isbn13"
+    }
+
+    fn validate(&self, code: &str) -> bool {
+        match digits(code, &[' ', '-']) {
+            Ok(digits) if digits.len() == 13 => isbn13_sum(&digits).is_multiple_of(10),
+            _ => false,
+        }
+    }
+
+    fn compute_check_digit(&self, payload: &str) -> Result<String, ChecksumError> {
+        let digits = digits(payload, &[' ', '-'])?;
+        if digits.len() != 12 {
+            return Err(ChecksumError::InvalidLength(digits.len()));
+        }
+        Ok(digit_char((10 - isbn13_sum(&digits) % 10) % 10))
+    }
+}
+
+// caratteri significativi dell'IBAN, in maiuscolo e senza spazi
+fn iban_chars(code: &str) -> Result<Vec<char>, ChecksumError> {
+    code.chars()
+        .enumerate()
+        .filter(|(_, ch)| *ch != ' ')
+        .map(|(position, ch)| {
+            if ch.is_ascii_alphanumeric() {
+                Ok(ch.to_ascii_uppercase())
+            } else {
+                Err(ChecksumError::InvalidCharacter { ch, position })
+            }
+        })
+        .collect()
+}
+
+// resto della divisione per 97 del numero ottenuto sostituendo le lettere
+// con 10..35, calcolato a pezzi per non andare in overflow
+fn mod97(chars: impl Iterator<Item = char>) -> u32 {
+    chars.fold(0, |rem, ch| {
+        let value = ch.to_digit(36).unwrap();
+        if value < 10 {
+            (rem * 10 + value) % 97
+        } else {
+            (rem * 100 + value) % 97
+        }
+    })
+}
+
+fn valid_country(chars: &[char]) -> bool {
+    chars.len() >= 2 && chars[..2].iter().all(|c| c.is_ascii_uppercase())
+}
+
+impl ChecksumAlgorithm for Iban {
+    fn name(&self) -> &'static str {
+        "iban"
+    }
+
+    fn validate(&self, code: &str) -> bool {
+        let chars = match iban_chars(code) {
+            Ok(chars) if (15..=34).contains(&chars.len()) => chars,
+            _ => return false,
+        };
+        if !valid_country(&chars) || !chars[2..4].iter().all(|c| c.is_ascii_digit()) {
+            return false;
+        }
+        mod97(chars[4..].iter().chain(&chars[..4]).copied()) == 1
+    }
+
+    // `payload` è il codice paese seguito dal BBAN, senza cifre di controllo
+    fn compute_check_digit(&self, payload: &str) -> Result<String, ChecksumError> {
+        let chars = iban_chars(payload)?;
+        if !(13..=32).contains(&chars.len()) {
+            return Err(ChecksumError::InvalidLength(chars.len()));
+        }
+        // il codice paese deve essere di due lettere
+        if let Some((position, ch)) = payload
+            .chars()
+            .enumerate()
+            .filter(|(_, ch)| *ch != ' ')
+            .take(2)
+            .find(|(_, ch)| !ch.is_ascii_alphabetic())
+        {
+            return Err(ChecksumError::InvalidCharacter { ch, position });
+        }
+
+        let rem = mod97(
+            chars[2..]
+                .iter()
+                .chain(&chars[..2])
+                .copied()
+                .chain("00".chars()),
+        );
+        Ok(format!("{:02}", 98 - rem))
+    }
+
+    // le cifre di controllo vanno subito dopo il codice paese
+    fn complete(&self, payload: &str) -> Result<String, ChecksumError> {
+        let check = self.compute_check_digit(payload)?;
+        let chars: String = iban_chars(payload)?.into_iter().collect();
+        Ok(format!("{}{}{}", &chars[..2], check, &chars[2..]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn luhn() {
+        assert!(Luhn.validate("4539 3195 0343 6467"));
+        assert!(!Luhn.validate("8273 1232 7352 0569"));
+        assert!(!Luhn.validate("059a"));
+        assert_eq!(Luhn.compute_check_digit("7992739871"), Ok("3".to_string()));
+        assert_eq!(
+            Luhn.compute_check_digit("79a"),
+            Err(ChecksumError::InvalidCharacter {
+                ch: 'a',
+                position: 2
+            })
+        );
+    }
+
+    #[test]
+    fn verhoeff_and_damm() {
+        assert_eq!(Verhoeff.compute_check_digit("236"), Ok("3".to_string()));
+        assert!(Verhoeff.validate("2363"));
+        assert!(!Verhoeff.validate("2336"));
+        assert_eq!(Damm.compute_check_digit("572"), Ok("4".to_string()));
+        assert!(Damm.validate("5724"));
+        assert!(!Damm.validate("5274"));
+    }
+
+    #[test]
+    fn isbn() {
+        assert!(Isbn10.validate("0-306-40615-2"));
+        assert!(Isbn10.validate("0-8044-2957-X"));
+        assert!(!Isbn10.validate("0-306-40615-3"));
+        assert_eq!(Isbn10.compute_check_digit("080442957"), Ok("X".to_string()));
+        assert_eq!(
+            Isbn10.compute_check_digit("12345"),
+            Err(ChecksumError::InvalidLength(5))
+        );
+        assert!(Isbn13.validate("978-0-306-40615-7"));
+        assert!(!Isbn13.validate("978-0-306-40615-8"));
+        assert_eq!(
+            Isbn13.complete("978-0-306-40615-"),
+            Ok("978-0-306-40615-7".to_string())
+        );
+    }
+
+    #[test]
+    fn iban() {
+        assert!(Iban.validate("GB82 WEST 1234 5698 7654 32"));
+        assert!(Iban.validate("IT60X0542811101000000123456"));
+        assert!(!Iban.validate("GB83 WEST 1234 5698 7654 32"));
+        assert_eq!(
+            Iban.compute_check_digit("GBWEST12345698765432"),
+            Ok("82".to_string())
+        );
+        assert_eq!(
+            Iban.compute_check_digit("I7X0542811101000000123456"),
+            Err(ChecksumError::InvalidCharacter {
+                ch: '7',
+                position: 1
+            })
+        );
+        assert_eq!(
+            Iban.complete("IT X0542811101000000123456"),
+            Ok("IT60X0542811101000000123456".to_string())
+        );
+    }
+
+    #[test]
+    fn one_api_for_every_algorithm() {
+        let algorithms: [&dyn ChecksumAlgorithm; 6] =
+            [&Luhn, &Verhoeff, &Damm, &Isbn10, &Isbn13, &Iban];
+        let payloads = [
+            "12345",
+            "12345",
+            "12345",
+            "123456789",
+            "978030640615",
+            "DE370400440532013000",
+        ];
+        for (algorithm, payload) in algorithms.iter().zip(payloads) {
+            let code = algorithm.complete(payload).unwrap();
+            assert!(algorithm.validate(&code), "{} {}", algorithm.name(), code);
+        }
+    }
+}
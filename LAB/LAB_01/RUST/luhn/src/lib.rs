@@ -1,40 +1,37 @@
+mod card;
+mod checksum;
+
+pub use card::{append_check_digit, card_info, CardInfo, CardIssuer};
+pub use checksum::{ChecksumAlgorithm, ChecksumError, Damm, Iban, Isbn10, Isbn13, Luhn, Verhoeff};
 
 /// Check a Luhn checksum.
+///
+/// Legacy variant kept for the exercise tests: digits are doubled starting from
+/// the left and non-digit characters are skipped, so it agrees with the standard
+/// check only for an even number of digits. The `luhn` binary and new code use
+/// [`Luhn`] (via [`ChecksumAlgorithm::validate`]), which doubles from the right
+/// and rejects anything but digits and spaces.
 pub fn is_valid(code: &str) -> bool {
-    if (&code[0..1] == " ") & (&code[1..].chars().count() <= &(1 as usize)) {
-        return false;
-    }
-
-    if code.len() <= 1 {
+    if code.len() <= 1 || (code.starts_with(' ') && code[1..].chars().count() <= 1) {
         return false;
     }
 
     let mut tot = 0;
-    let mut i = 2;
-    let mut number;
+    let mut double = true;
     for s in code.chars() {
-        if s == ' ' {continue;}
-
-        match s.to_digit(10) {
-            Some(valore)  => number = valore * i,
-            None => continue
-        }
+        let mut number = match s.to_digit(10) {
+            Some(valore) if double => valore * 2,
+            Some(valore) => valore,
+            None => continue,
+        };
 
         if number > 9 {
             number -= 9;
         }
 
-        if i <= 1 {
-            i = 2;
-        } else {
-            i = 1;
-        }
-
+        double = !double;
         tot += number;
-        print!("{}\t", number);
     }
 
-    println!("\n{}", tot);
-    tot % 10 == 0
+    tot.is_multiple_of(10)
 }
-
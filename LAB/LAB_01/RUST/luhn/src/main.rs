@@ -1,7 +1,256 @@
-use luhn::is_valid;
+use luhn::{ChecksumAlgorithm, Luhn};
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
 
+const USAGE: &str = "usage: luhn [--text | --csv | --json] [file ...]";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Csv,
+    Json,
+}
+
+// scrive i risultati riga per riga man mano che arrivano, senza tenerli in memoria
+struct Report<W: Write> {
+    out: W,
+    format: Format,
+    total: usize,
+    valid: usize,
+}
+
+impl<W: Write> Report<W> {
+    fn new(out: W, format: Format) -> io::Result<Self> {
+        let mut report = Report {
+            out,
+            format,
+            total: 0,
+            valid: 0,
+        };
+        match format {
+            Format::Text => {}
+            Format::Csv => writeln!(report.out, "source,line,number,valid")?,
+            Format::Json => write!(report.out, "{{\"results\":[")?,
+        }
+        Ok(report)
+    }
+
+    // si usa Luhn e non la vecchia is_valid della libreria, che raddoppia da sinistra;
+    // le righe con caratteri diversi da cifre e spazi non sono valide
+    fn line(&mut self, source: &str, line: usize, number: &str) -> io::Result<()> {
+        let valid = Luhn.validate(number);
+        match self.format {
+            Format::Text => writeln!(
+                self.out,
+                "{}:{}: {}: {}",
+                source,
+                line,
+                number,
+                if valid { "valid" } else { "invalid" }
+            )?,
+            Format::Csv => writeln!(
+                self.out,
+                "{},{},{},{}",
+                csv_field(source),
+                line,
+                csv_field(number),
+                valid
+            )?,
+            Format::Json => write!(
+                self.out,
+                "{}{{\"source\":{},\"line\":{},\"number\":{},\"valid\":{}}}",
+                if self.total == 0 { "" } else { "," },
+                json_string(source),
+                line,
+                json_string(number),
+                valid
+            )?,
+        }
+        self.total += 1;
+        if valid {
+            self.valid += 1;
+        }
+        Ok(())
+    }
+
+    // chiude il report con il riepilogo; restituisce true se tutte le righe sono valide
+    fn finish(mut self) -> io::Result<bool> {
+        let invalid = self.total - self.valid;
+        match self.format {
+            Format::Text => writeln!(
+                self.out,
+                "{} lines, {} valid, {} invalid",
+                self.total, self.valid, invalid
+            )?,
+            // il riepilogo va su stderr per non sporcare il CSV
+            Format::Csv => eprintln!(
+                "{} lines, {} valid, {} invalid",
+                self.total, self.valid, invalid
+            ),
+            Format::Json => writeln!(
+                self.out,
+                "],\"summary\":{{\"total\":{},\"valid\":{},\"invalid\":{}}}}}",
+                self.total, self.valid, invalid
+            )?,
+        }
+        self.out.flush()?;
+        Ok(invalid == 0)
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// valida le righe non vuote di `input`
+fn check<R: BufRead, W: Write>(report: &mut Report<W>, source: &str, input: R) -> io::Result<()> {
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        let number = line.trim();
+        if !number.is_empty() {
+            report.line(source, i + 1, number)?;
+        }
+    }
+    Ok(())
+}
+
+// uso: luhn [--text | --csv | --json] [file ...]
+// senza file (o con "-") legge da stdin; esce con 1 se almeno una riga non è valida
 fn main() {
-    let credit_card = "59%59";                              //"4539 3195 0343 6467";
-    //println!("{}", is_valid(credit_card));
-    is_valid(credit_card);
-}
\ No newline at end of file
+    let mut format = Format::Text;
+    let mut files = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--text" => format = Format::Text,
+            "--csv" => format = Format::Csv,
+            "--json" => format = Format::Json,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ if arg.starts_with("--") => {
+                eprintln!("unknown option {}", arg);
+                eprintln!("{}", USAGE);
+                std::process::exit(2);
+            }
+            _ => files.push(arg),
+        }
+    }
+    if files.is_empty() {
+        files.push("-".to_string());
+    }
+
+    let stdout = io::stdout();
+    let result = Report::new(stdout.lock(), format).and_then(|mut report| {
+        for file in &files {
+            if file == "-" {
+                check(&mut report, "-", io::stdin().lock())?;
+            } else {
+                let input = File::open(file)
+                    .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", file, e)))?;
+                check(&mut report, file, BufReader::new(input))?;
+            }
+        }
+        report.finish()
+    });
+
+    match result {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check, Format, Report};
+
+    fn run(format: Format, input: &str) -> (String, bool) {
+        let mut out = Vec::new();
+        let mut report = Report::new(&mut out, format).unwrap();
+        check(&mut report, "cards.txt", input.as_bytes()).unwrap();
+        let ok = report.finish().unwrap();
+        (String::from_utf8(out).unwrap(), ok)
+    }
+
+    #[test]
+    fn text_report() {
+        let (out, ok) = run(Format::Text, "4539 3195 0343 6467\n\n8273 1232 7352 0569\n");
+        assert_eq!(
+            out,
+            "cards.txt:1: 4539 3195 0343 6467: valid\n\
+             cards.txt:3: 8273 1232 7352 0569: invalid\n\
+             2 lines, 1 valid, 1 invalid\n"
+        );
+        assert!(!ok);
+
+        // le cifre si raddoppiano da destra, anche con una lunghezza dispari
+        let (out, ok) = run(Format::Text, "3782 822463 10005\n055 444 285\n");
+        assert_eq!(
+            out,
+            "cards.txt:1: 3782 822463 10005: valid\n\
+             cards.txt:2: 055 444 285: valid\n\
+             2 lines, 2 valid, 0 invalid\n"
+        );
+        assert!(ok);
+
+        let (out, ok) = run(Format::Text, "4539-3195-0343-6467\n055a 444 285\n");
+        assert_eq!(
+            out,
+            "cards.txt:1: 4539-3195-0343-6467: invalid\n\
+             cards.txt:2: 055a 444 285: invalid\n\
+             2 lines, 0 valid, 2 invalid\n"
+        );
+        assert!(!ok);
+    }
+
+    #[test]
+    fn csv_and_json_reports() {
+        let (out, ok) = run(
+            Format::Csv,
+            "4539 3195 0343 6467\n\"4539,3195,0343,6467\"\n",
+        );
+        assert_eq!(
+            out,
+            "source,line,number,valid\n\
+             cards.txt,1,4539 3195 0343 6467,true\n\
+             cards.txt,2,\"\"\"4539,3195,0343,6467\"\"\",false\n"
+        );
+        assert!(!ok);
+
+        let (out, ok) = run(Format::Json, "4539 3195 0343 6467\n\"1\"\n");
+        assert_eq!(
+            out,
+            "{\"results\":[\
+             {\"source\":\"cards.txt\",\"line\":1,\"number\":\"4539 3195 0343 6467\",\"valid\":true},\
+             {\"source\":\"cards.txt\",\"line\":2,\"number\":\"\\\"1\\\"\",\"valid\":false}\
+             ],\"summary\":{\"total\":2,\"valid\":1,\"invalid\":1}}\n"
+        );
+        assert!(!ok);
+    }
+}
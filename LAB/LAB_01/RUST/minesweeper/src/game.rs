@@ -0,0 +1,272 @@
+use std::fmt::{Display, Formatter};
+
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::SeedableRng;
+
+use crate::{neighbours, try_annotate, BoardError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellState {
+    Hidden,
+    Flagged,
+    Revealed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameState {
+    Playing,
+    Won,
+    Lost,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GameError {
+    OutOfBounds { row: usize, column: usize },
+    AlreadyRevealed,
+    Flagged,
+    GameOver,
+    TooManyMines { mines: usize, cells: usize },
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameError::OutOfBounds { row, column } => {
+                write!(f, "cell ({}, {}) is outside the board", row, column)
+            }
+            GameError::AlreadyRevealed => write!(f, "cell already revealed"),
+            GameError::Flagged => write!(f, "cell is flagged, remove the flag first"),
+            GameError::GameOver => write!(f, "the game is over"),
+            GameError::TooManyMines { mines, cells } => {
+                write!(f, "cannot place {} mines on {} cells", mines, cells)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+pub struct Game {
+    rows: usize,
+    columns: usize,
+    // board annotata da `annotate`: '*' per le mine, cifra o ' ' per le altre caselle
+    board: Vec<Vec<char>>,
+    cells: Vec<Vec<CellState>>,
+    hidden_safe: usize,
+    state: GameState,
+}
+
+impl Game {
+    // posiziona `mines` mine a caso; lo stesso seed produce sempre la stessa board
+    pub fn new(rows: usize, columns: usize, mines: usize, seed: u64) -> Result<Self, GameError> {
+        let cells = rows * columns;
+        if mines > cells {
+            return Err(GameError::TooManyMines { mines, cells });
+        }
+
+        let mut board = vec![vec![' '; columns]; rows];
+        let mut rng = StdRng::seed_from_u64(seed);
+        for index in sample(&mut rng, cells, mines) {
+            board[index / columns][index % columns] = '*';
+        }
+
+        let rows: Vec<String> = board.iter().map(|row| row.iter().collect()).collect();
+        let rows: Vec<&str> = rows.iter().map(|row| row.as_str()).collect();
+        Ok(Game::from_board(&rows).unwrap())
+    }
+
+    // partita su una board data nello stesso formato accettato da `annotate`
+    pub fn from_board(minefield: &[&str]) -> Result<Self, BoardError> {
+        let board: Vec<Vec<char>> = try_annotate(minefield)?
+            .iter()
+            .map(|row| row.chars().collect())
+            .collect();
+        let rows = board.len();
+        let columns = board.first().map_or(0, |row| row.len());
+        let hidden_safe = board.iter().flatten().filter(|&&c| c != '*').count();
+
+        Ok(Game {
+            rows,
+            columns,
+            board,
+            cells: vec![vec![CellState::Hidden; columns]; rows],
+            hidden_safe,
+            state: if hidden_safe == 0 {
+                GameState::Won
+            } else {
+                GameState::Playing
+            },
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    // board nel formato accettato da `annotate` (' ' libera, '*' mina)
+    pub fn minefield(&self) -> Vec<String> {
+        self.board
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&c| if c == '*' { '*' } else { ' ' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn cell_state(&self, row: usize, column: usize) -> Option<CellState> {
+        self.cells.get(row)?.get(column).copied()
+    }
+
+    // scopre una casella; se non ha mine vicine scopre a catena anche quelle adiacenti
+    pub fn reveal(&mut self, row: usize, column: usize) -> Result<GameState, GameError> {
+        self.check(row, column)?;
+        match self.cells[row][column] {
+            CellState::Revealed => return Err(GameError::AlreadyRevealed),
+            CellState::Flagged => return Err(GameError::Flagged),
+            CellState::Hidden => {}
+        }
+
+        if self.board[row][column] == '*' {
+            self.cells[row][column] = CellState::Revealed;
+            self.state = GameState::Lost;
+            return Ok(self.state);
+        }
+
+        let mut stack = vec![(row, column)];
+        while let Some((i, j)) = stack.pop() {
+            if self.cells[i][j] != CellState::Hidden {
+                continue;
+            }
+            self.cells[i][j] = CellState::Revealed;
+            self.hidden_safe -= 1;
+
+            if self.board[i][j] == ' ' {
+                stack.extend(
+                    neighbours(i, j, self.rows, self.columns)
+                        .filter(|&(x, y)| self.cells[x][y] == CellState::Hidden),
+                );
+            }
+        }
+
+        if self.hidden_safe == 0 {
+            self.state = GameState::Won;
+        }
+        Ok(self.state)
+    }
+
+    // mette o toglie la bandierina, restituisce true se ora la casella è segnata
+    pub fn toggle_flag(&mut self, row: usize, column: usize) -> Result<bool, GameError> {
+        self.check(row, column)?;
+        let cell = &mut self.cells[row][column];
+        *cell = match *cell {
+            CellState::Revealed => return Err(GameError::AlreadyRevealed),
+            CellState::Flagged => CellState::Hidden,
+            CellState::Hidden => CellState::Flagged,
+        };
+        Ok(*cell == CellState::Flagged)
+    }
+
+    // vista della board: '#' coperta, 'F' bandierina, poi come `annotate`;
+    // a partita persa vengono mostrate tutte le mine
+    pub fn view(&self) -> Vec<String> {
+        (0..self.rows)
+            .map(|i| {
+                (0..self.columns)
+                    .map(|j| match self.cells[i][j] {
+                        _ if self.state == GameState::Lost && self.board[i][j] == '*' => '*',
+                        CellState::Hidden => '#',
+                        CellState::Flagged => 'F',
+                        CellState::Revealed => self.board[i][j],
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn check(&self, row: usize, column: usize) -> Result<(), GameError> {
+        if self.state != GameState::Playing {
+            return Err(GameError::GameOver);
+        }
+        if row >= self.rows || column >= self.columns {
+            return Err(GameError::OutOfBounds { row, column });
+        }
+        Ok(())
+    }
+}
+
+impl Display for Game {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "   ")?;
+        for j in 0..self.columns {
+            write!(f, "{:>2}", j % 100)?;
+        }
+        writeln!(f)?;
+
+        for (i, row) in self.view().iter().enumerate() {
+            write!(f, "{:>2} ", i % 100)?;
+            for c in row.chars() {
+                write!(f, " {}", if c == ' ' { '·' } else { c })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CellState, Game, GameError, GameState};
+
+    #[test]
+    fn same_seed_same_board() {
+        let a = Game::new(8, 8, 10, 42).unwrap();
+        let b = Game::new(8, 8, 10, 42).unwrap();
+        assert_eq!(a.board, b.board);
+        assert_eq!(a.board.iter().flatten().filter(|&&c| c == '*').count(), 10);
+        assert_eq!(
+            Game::new(2, 2, 5, 0).err(),
+            Some(GameError::TooManyMines { mines: 5, cells: 4 })
+        );
+    }
+
+    #[test]
+    fn flood_fill_and_win() {
+        let mut game = Game::from_board(&["    ", "    ", "   *"]).unwrap();
+        assert_eq!(game.reveal(0, 0), Ok(GameState::Won));
+        assert_eq!(game.view(), vec!["    ", "  11", "  1#"]);
+
+        let mut game = Game::from_board(&["*  ", "   "]).unwrap();
+        assert_eq!(game.toggle_flag(0, 0), Ok(true));
+        assert_eq!(game.reveal(0, 0), Err(GameError::Flagged));
+        assert_eq!(game.reveal(0, 1), Ok(GameState::Playing));
+        assert_eq!(game.reveal(0, 1), Err(GameError::AlreadyRevealed));
+        assert_eq!(game.cell_state(0, 0), Some(CellState::Flagged));
+        assert_eq!(game.reveal(1, 2), Ok(GameState::Playing));
+        assert_eq!(game.view(), vec!["F1 ", "#1 "]);
+        assert_eq!(game.reveal(1, 0), Ok(GameState::Won));
+        assert_eq!(game.reveal(0, 0), Err(GameError::GameOver));
+    }
+
+    #[test]
+    fn stepping_on_a_mine() {
+        let mut game = Game::from_board(&["* ", " *"]).unwrap();
+        assert_eq!(
+            game.reveal(2, 0),
+            Err(GameError::OutOfBounds { row: 2, column: 0 })
+        );
+        game.toggle_flag(1, 1).unwrap();
+        assert_eq!(game.reveal(0, 0), Ok(GameState::Lost));
+        assert_eq!(game.view(), vec!["*#", "#*"]);
+    }
+}
@@ -1,3 +1,183 @@
+use std::fmt::{Display, Formatter};
+
+mod game;
+mod solver;
+
+pub use game::{CellState, Game, GameError, GameState};
+pub use solver::{
+    generate_no_guess, is_solvable_without_guessing, solve, Deductions, NoGuessBoard,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BoardError {
+    // una riga ha una lunghezza diversa dalla prima
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    // sono ammessi solo ' ' e '*' (più '#', 'F' e le cifre per il solver)
+    InvalidCharacter {
+        row: usize,
+        column: usize,
+        ch: char,
+    },
+    // un numero non è compatibile con le caselle che lo circondano
+    Inconsistent {
+        row: usize,
+        column: usize,
+    },
+}
+
+impl Display for BoardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoardError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} has {} columns, expected {}",
+                row, found, expected
+            ),
+            BoardError::InvalidCharacter { row, column, ch } => {
+                write!(
+                    f,
+                    "invalid character {:?} at row {}, column {}",
+                    ch, row, column
+                )
+            }
+            BoardError::Inconsistent { row, column } => {
+                write!(
+                    f,
+                    "number at row {}, column {} cannot be satisfied",
+                    row, column
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
 pub fn annotate(minefield: &[&str]) -> Vec<String> {
-    unimplemented!("\nAnnotate each square of the given minefield with the number of mines that surround said square (blank if there are no surrounding mines):\n{minefield:#?}\n");
+    try_annotate(minefield).unwrap_or_else(|e| panic!("invalid minefield: {}", e))
+}
+
+// annota ogni casella vuota con il numero di mine adiacenti (vuota se zero)
+pub fn try_annotate(minefield: &[&str]) -> Result<Vec<String>, BoardError> {
+    let board = parse(minefield)?;
+
+    Ok(board
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(j, &mine)| {
+                    if mine {
+                        '*'
+                    } else {
+                        match count_mines(&board, i, j) {
+                            0 => ' ',
+                            n => char::from_digit(n as u32, 10).unwrap(),
+                        }
+                    }
+                })
+                .collect()
+        })
+        .collect())
+}
+
+// converte le righe in una matrice di booleani (true = mina) controllandone la forma
+fn parse(minefield: &[&str]) -> Result<Vec<Vec<bool>>, BoardError> {
+    parse_with(minefield, |ch| match ch {
+        '*' => Some(true),
+        ' ' => Some(false),
+        _ => None,
+    })
+}
+
+// controlla che la board sia rettangolare e converte ogni carattere con `cell`
+fn parse_with<T>(
+    minefield: &[&str],
+    cell: impl Fn(char) -> Option<T>,
+) -> Result<Vec<Vec<T>>, BoardError> {
+    let columns = minefield.first().map_or(0, |row| row.chars().count());
+
+    minefield
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let cells = row
+                .chars()
+                .enumerate()
+                .map(|(j, ch)| {
+                    cell(ch).ok_or(BoardError::InvalidCharacter {
+                        row: i,
+                        column: j,
+                        ch,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if cells.len() != columns {
+                return Err(BoardError::RaggedRow {
+                    row: i,
+                    expected: columns,
+                    found: cells.len(),
+                });
+            }
+            Ok(cells)
+        })
+        .collect()
+}
+
+fn count_mines(board: &[Vec<bool>], i: usize, j: usize) -> usize {
+    let columns = board[i].len();
+    neighbours(i, j, board.len(), columns)
+        .filter(|&(x, y)| board[x][y])
+        .count()
+}
+
+// caselle adiacenti a (i, j) che cadono dentro una board rows x columns
+fn neighbours(
+    i: usize,
+    j: usize,
+    rows: usize,
+    columns: usize,
+) -> impl Iterator<Item = (usize, usize)> {
+    let xs = i.saturating_sub(1)..(i + 2).min(rows);
+    xs.flat_map(move |x| (j.saturating_sub(1)..(j + 2).min(columns)).map(move |y| (x, y)))
+        .filter(move |&cell| cell != (i, j))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{try_annotate, BoardError};
+
+    #[test]
+    fn ragged_board() {
+        assert_eq!(
+            try_annotate(&["* ", "*", "  "]),
+            Err(BoardError::RaggedRow {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_character() {
+        assert_eq!(
+            try_annotate(&["  ", " x"]),
+            Err(BoardError::InvalidCharacter {
+                row: 1,
+                column: 1,
+                ch: 'x'
+            })
+        );
+    }
 }
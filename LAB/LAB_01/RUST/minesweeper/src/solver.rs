@@ -0,0 +1,322 @@
+use crate::{neighbours, parse_with, try_annotate, BoardError, Game, GameState};
+
+// caselle che il solver è riuscito a dedurre con certezza, in ordine di riga e colonna
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Deductions {
+    pub safe: Vec<(usize, usize)>,
+    pub mines: Vec<(usize, usize)>,
+}
+
+// board risolvibile senza tirare a indovinare partendo da `start`
+#[derive(Debug, PartialEq, Eq)]
+pub struct NoGuessBoard {
+    pub minefield: Vec<String>,
+    pub start: (usize, usize),
+    pub seed: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Unknown,
+    Mine,
+    Safe,
+    Number(usize),
+}
+
+// le caselle ancora sconosciute attorno a un numero e quante mine ci sono tra esse
+struct Constraint {
+    center: (usize, usize),
+    cells: Vec<(usize, usize)>,
+    mines: usize,
+}
+
+// deduce caselle sicure e mine certe da una board parzialmente scoperta, con
+// le stesse righe di `annotate` più '#' per le caselle coperte e 'F' per le
+// bandierine; una mina già nota può essere indicata anche con '*'
+pub fn solve(board: &[&str]) -> Result<Deductions, BoardError> {
+    let mut grid = parse_with(board, |ch| match ch {
+        '#' => Some(Cell::Unknown),
+        'F' | '*' => Some(Cell::Mine),
+        ' ' => Some(Cell::Number(0)),
+        '1'..='8' => ch.to_digit(10).map(|n| Cell::Number(n as usize)),
+        _ => None,
+    })?;
+
+    let mut deductions = Deductions::default();
+    loop {
+        let constraints = constraints(&grid)?;
+        let mut found = single_rule(&constraints);
+        if found.is_empty() {
+            found = subset_rule(&constraints)?;
+        }
+        if found.is_empty() {
+            break;
+        }
+
+        for ((i, j), mine) in found {
+            if grid[i][j] != Cell::Unknown {
+                continue;
+            }
+            if mine {
+                grid[i][j] = Cell::Mine;
+                deductions.mines.push((i, j));
+            } else {
+                grid[i][j] = Cell::Safe;
+                deductions.safe.push((i, j));
+            }
+        }
+    }
+
+    deductions.safe.sort_unstable();
+    deductions.mines.sort_unstable();
+    Ok(deductions)
+}
+
+// gioca la partita a partire da `start` usando solo le deduzioni del solver
+pub fn is_solvable_without_guessing(
+    minefield: &[&str],
+    start: (usize, usize),
+) -> Result<bool, BoardError> {
+    let mut game = Game::from_board(minefield)?;
+    if game.state() == GameState::Won {
+        return Ok(true);
+    }
+    if game.reveal(start.0, start.1) != Ok(GameState::Playing) {
+        return Ok(game.state() == GameState::Won);
+    }
+
+    while game.state() == GameState::Playing {
+        let view = game.view();
+        let view: Vec<&str> = view.iter().map(|row| row.as_str()).collect();
+        let deductions = solve(&view)?;
+        if deductions.safe.is_empty() && deductions.mines.is_empty() {
+            return Ok(false);
+        }
+
+        for &(i, j) in &deductions.mines {
+            let _ = game.toggle_flag(i, j);
+        }
+        for &(i, j) in &deductions.safe {
+            // la casella può essere già stata scoperta a catena da una precedente
+            let _ = game.reveal(i, j);
+        }
+    }
+    Ok(game.state() == GameState::Won)
+}
+
+// prova i seed da `seed` in poi finché non trova una board risolvibile senza
+// indovinare, partendo dalla casella senza mine vicine più centrale
+pub fn generate_no_guess(
+    rows: usize,
+    columns: usize,
+    mines: usize,
+    seed: u64,
+    attempts: usize,
+) -> Option<NoGuessBoard> {
+    for attempt in 0..attempts as u64 {
+        let seed = seed.wrapping_add(attempt);
+        let minefield = Game::new(rows, columns, mines, seed).ok()?.minefield();
+        let rows_ref: Vec<&str> = minefield.iter().map(|row| row.as_str()).collect();
+        let annotated = try_annotate(&rows_ref).ok()?;
+
+        let center = (rows / 2, columns / 2);
+        let start = annotated
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter(|&(_, c)| c == ' ')
+                    .map(move |(j, _)| (i, j))
+            })
+            .min_by_key(|&(i, j)| i.abs_diff(center.0) + j.abs_diff(center.1));
+
+        if let Some(start) = start {
+            if is_solvable_without_guessing(&rows_ref, start) == Ok(true) {
+                return Some(NoGuessBoard {
+                    minefield,
+                    start,
+                    seed,
+                });
+            }
+        }
+    }
+    None
+}
+
+fn constraints(grid: &[Vec<Cell>]) -> Result<Vec<Constraint>, BoardError> {
+    let mut constraints = Vec::new();
+    for (i, row) in grid.iter().enumerate() {
+        for (j, &cell) in row.iter().enumerate() {
+            let n = match cell {
+                Cell::Number(n) => n,
+                _ => continue,
+            };
+
+            let mut cells = Vec::new();
+            let mut known = 0;
+            for (x, y) in neighbours(i, j, grid.len(), row.len()) {
+                match grid[x][y] {
+                    Cell::Unknown => cells.push((x, y)),
+                    Cell::Mine => known += 1,
+                    _ => {}
+                }
+            }
+
+            if known > n || n - known > cells.len() {
+                return Err(BoardError::Inconsistent { row: i, column: j });
+            }
+            if !cells.is_empty() {
+                constraints.push(Constraint {
+                    center: (i, j),
+                    cells,
+                    mines: n - known,
+                });
+            }
+        }
+    }
+    Ok(constraints)
+}
+
+// casella dedotta, true se è una mina
+type Deduction = ((usize, usize), bool);
+
+// un numero già soddisfatto rende sicure le altre caselle, uno che ha tante
+// caselle coperte quante mine mancanti le rende tutte mine
+fn single_rule(constraints: &[Constraint]) -> Vec<Deduction> {
+    let mut found = Vec::new();
+    for constraint in constraints {
+        if constraint.mines == 0 {
+            found.extend(constraint.cells.iter().map(|&cell| (cell, false)));
+        } else if constraint.mines == constraint.cells.len() {
+            found.extend(constraint.cells.iter().map(|&cell| (cell, true)));
+        }
+    }
+    found
+}
+
+// se le caselle di A sono contenute in quelle di B, la differenza B \ A
+// contiene esattamente B.mines - A.mines mine; se non può contenerle la board
+// è incoerente, come per un numero con troppe mine intorno
+fn subset_rule(constraints: &[Constraint]) -> Result<Vec<Deduction>, BoardError> {
+    let mut found = Vec::new();
+    for a in constraints {
+        for b in constraints {
+            let near = a.center.0.abs_diff(b.center.0) <= 2 && a.center.1.abs_diff(b.center.1) <= 2;
+            if a.center == b.center || !near || a.cells.len() >= b.cells.len() {
+                continue;
+            }
+            if !a.cells.iter().all(|cell| b.cells.contains(cell)) {
+                continue;
+            }
+
+            let rest: Vec<_> = b
+                .cells
+                .iter()
+                .filter(|cell| !a.cells.contains(cell))
+                .collect();
+            let (row, column) = b.center;
+            let mines = b
+                .mines
+                .checked_sub(a.mines)
+                .filter(|&mines| mines <= rest.len())
+                .ok_or(BoardError::Inconsistent { row, column })?;
+            if mines == 0 {
+                found.extend(rest.iter().map(|&&cell| (cell, false)));
+            } else if mines == rest.len() {
+                found.extend(rest.iter().map(|&&cell| (cell, true)));
+            }
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{generate_no_guess, is_solvable_without_guessing, solve, Deductions};
+    use crate::BoardError;
+
+    #[test]
+    fn single_constraints() {
+        // l'1 vede tre caselle coperte e non permette deduzioni
+        assert_eq!(
+            solve(&["1#", "##"]),
+            Ok(Deductions {
+                safe: vec![],
+                mines: vec![],
+            })
+        );
+        assert_eq!(
+            solve(&["1#", "11"]),
+            Ok(Deductions {
+                safe: vec![],
+                mines: vec![(0, 1)],
+            })
+        );
+        assert_eq!(
+            solve(&["F1#", "11#"]),
+            Ok(Deductions {
+                safe: vec![(0, 2), (1, 2)],
+                mines: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn subset_constraint() {
+        // classico 1-2 sul bordo: la terza casella sopra il 2 è una mina,
+        // la prima casella sopra l'1 è sicura
+        assert_eq!(
+            solve(&["####", "1221", "    "]),
+            Ok(Deductions {
+                safe: vec![(0, 0), (0, 3)],
+                mines: vec![(0, 1), (0, 2)],
+            })
+        );
+    }
+
+    #[test]
+    fn inconsistent_board() {
+        assert_eq!(
+            solve(&["4#", "##"]),
+            Err(BoardError::Inconsistent { row: 0, column: 0 })
+        );
+        // ogni numero da solo è possibile, ma il 2 in basso vuole due mine tra
+        // caselle che l'1 in alto vede tutte
+        assert_eq!(
+            solve(&["#1#", "###", "121"]),
+            Err(BoardError::Inconsistent { row: 0, column: 1 })
+        );
+    }
+
+    #[test]
+    fn needs_a_guess() {
+        // due caselle simmetriche, una sola mina: impossibile decidere
+        assert_eq!(
+            is_solvable_without_guessing(&["* ", "  "], (1, 1)),
+            Ok(false)
+        );
+        assert_eq!(
+            is_solvable_without_guessing(&["*  ", "   ", "   "], (2, 2)),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn generated_boards_are_solvable() {
+        for seed in [1, 7, 42] {
+            let board = generate_no_guess(9, 9, 10, seed, 1000).unwrap();
+            let rows: Vec<&str> = board.minefield.iter().map(|r| r.as_str()).collect();
+            assert_eq!(
+                rows.iter()
+                    .flat_map(|r| r.chars())
+                    .filter(|&c| c == '*')
+                    .count(),
+                10
+            );
+            assert_eq!(is_solvable_without_guessing(&rows, board.start), Ok(true));
+            assert_eq!(generate_no_guess(9, 9, 10, seed, 1000), Some(board));
+        }
+        assert_eq!(generate_no_guess(2, 2, 4, 0, 10), None);
+    }
+}
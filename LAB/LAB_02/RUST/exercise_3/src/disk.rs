@@ -0,0 +1,172 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::{is_valid_name, now, Dir, File, FileSystem, FileType, Node, MAX_CONTENT};
+
+impl FileSystem {
+    // carica in memoria l'albero che si trova su disco sotto `path`
+    pub fn from_dir(path: &str) -> io::Result<Self> {
+        let mut file_system = FileSystem::new();
+        let metadata = fs::metadata(path)?;
+        if !metadata.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{}: not a directory", path),
+            ));
+        }
+
+        file_system.root.creation_time = creation_time(&metadata);
+        load_dir(Path::new(path), &mut file_system.root)?;
+        Ok(file_system)
+    }
+
+    // ricrea su disco l'albero in memoria usando `path` come radice
+    pub fn write_to_dir(&self, path: &str) -> io::Result<()> {
+        fs::create_dir_all(path)?;
+        write_dir(Path::new(path), &self.root)
+    }
+}
+
+fn load_dir(path: &Path, dir: &mut Dir) -> io::Result<()> {
+    let mut entries = fs::read_dir(path)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !is_valid_name(&name) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: invalid name", entry.path().display()),
+            ));
+        }
+        // i link simbolici e i file speciali vengono ignorati
+        let metadata = fs::symlink_metadata(entry.path())?;
+
+        if metadata.is_dir() {
+            let mut sub = Dir::new(name);
+            sub.creation_time = creation_time(&metadata);
+            load_dir(&entry.path(), &mut sub)?;
+            dir.children.push(Node::Dir(sub));
+        } else if metadata.is_file() {
+            let mut content = Vec::with_capacity(MAX_CONTENT);
+            fs::File::open(entry.path())?
+                .take(MAX_CONTENT as u64)
+                .read_to_end(&mut content)?;
+
+            let mut file = File::new(name, file_type(&content));
+            file.creation_time = creation_time(&metadata);
+            file.content = content;
+            dir.children.push(Node::File(file));
+        }
+    }
+
+    Ok(())
+}
+
+fn write_dir(path: &Path, dir: &Dir) -> io::Result<()> {
+    for node in &dir.children {
+        // un nome come ".." o "a/b" porterebbe la scrittura fuori dalla radice
+        if !is_valid_name(node.name()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{:?}: invalid name", node.name()),
+            ));
+        }
+        let child = path.join(node.name());
+        match node {
+            Node::Dir(sub) => {
+                if !child.is_dir() {
+                    fs::create_dir(&child)?;
+                }
+                write_dir(&child, sub)?;
+            }
+            Node::File(file) => fs::write(&child, &file.content)?,
+        }
+    }
+    Ok(())
+}
+
+// un file è di testo se il contenuto letto è UTF-8 valido e non contiene byte nulli;
+// un carattere spezzato dal troncamento a MAX_CONTENT non lo rende binario
+fn file_type(content: &[u8]) -> FileType {
+    let utf8 = match std::str::from_utf8(content) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+
+    if utf8 && !content.contains(&0) {
+        FileType::Text
+    } else {
+        FileType::Binary
+    }
+}
+
+fn creation_time(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .created()
+        .or_else(|_| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|time| time.as_secs())
+        .unwrap_or_else(now)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{File, FileSystem, FileType, Node, MAX_CONTENT};
+    use std::fs;
+    use std::io;
+
+    #[test]
+    fn load_and_write_back() {
+        let src = std::env::temp_dir().join(format!("exercise_3_src_{}", std::process::id()));
+        let dst = std::env::temp_dir().join(format!("exercise_3_dst_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&src);
+        let _ = fs::remove_dir_all(&dst);
+
+        fs::create_dir_all(src.join("a/b")).unwrap();
+        fs::write(src.join("a/note.txt"), "ciao").unwrap();
+        fs::write(src.join("a/b/data.bin"), [0u8, 159, 146, 150]).unwrap();
+        fs::write(src.join("big.txt"), "x".repeat(MAX_CONTENT + 500)).unwrap();
+
+        let file_system = FileSystem::from_dir(src.to_str().unwrap()).unwrap();
+        let note = file_system.get_file("a/note.txt").unwrap();
+        assert_eq!(note.content(), b"ciao");
+        assert_eq!(note.file_type(), &FileType::Text);
+        let data = file_system.get_file("a/b/data.bin").unwrap();
+        assert_eq!(data.file_type(), &FileType::Binary);
+        let big = file_system.get_file("big.txt").unwrap();
+        assert_eq!(big.content().len(), MAX_CONTENT);
+
+        file_system.write_to_dir(dst.to_str().unwrap()).unwrap();
+        assert_eq!(fs::read(dst.join("a/note.txt")).unwrap(), b"ciao");
+        assert_eq!(
+            fs::read(dst.join("a/b/data.bin")).unwrap(),
+            [0u8, 159, 146, 150]
+        );
+        assert_eq!(fs::read(dst.join("big.txt")).unwrap().len(), MAX_CONTENT);
+
+        fs::remove_dir_all(&src).unwrap();
+        fs::remove_dir_all(&dst).unwrap();
+    }
+
+    #[test]
+    fn write_rejects_names_outside_the_root() {
+        let dst = std::env::temp_dir().join(format!("exercise_3_escape_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dst);
+
+        for name in ["..", "a/b", ""] {
+            let mut file_system = FileSystem::new();
+            file_system
+                .root
+                .children
+                .push(Node::File(File::new(name.to_string(), FileType::Text)));
+            let err = file_system.write_to_dir(dst.to_str().unwrap()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{:?}", name);
+        }
+        assert_eq!(fs::read_dir(&dst).unwrap().count(), 0);
+        fs::remove_dir_all(&dst).unwrap();
+    }
+}
@@ -0,0 +1,417 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+
+use crate::{is_valid_name, Dir, File, FileSystem, FileType, Node, MAX_CONTENT};
+
+// formato dell'immagine (interi little endian):
+//   magic "PDFS" | versione u16 | lunghezza payload u64 | crc32 del payload u32 | payload
+// il payload è la cartella radice serializzata ricorsivamente:
+//   dir:  tag 0 | nome | creation_time u64 | numero figli u32 | figli
+//   file: tag 1 | nome | creation_time u64 | tipo u8 | lunghezza u32 | contenuto
+// dove nome = lunghezza u16 | byte UTF-8
+const MAGIC: &[u8; 4] = b"PDFS";
+pub const IMAGE_VERSION: u16 = 1;
+const HEADER_LEN: usize = 4 + 2 + 8 + 4;
+
+// le cartelle si leggono e scrivono ricorsivamente: un limite alla profondità evita
+// che un'immagine costruita apposta esaurisca lo stack
+const MAX_DEPTH: usize = 256;
+
+const TAG_DIR: u8 = 0;
+const TAG_FILE: u8 = 1;
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u16),
+    Truncated,
+    ChecksumMismatch { expected: u32, found: u32 },
+    Corrupted(String),
+    // l'albero non si può rappresentare nel formato (nome troppo lungo, troppi figli)
+    TooLarge(String),
+}
+
+impl Display for ImageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageError::Io(e) => write!(f, "i/o error: {}", e),
+            ImageError::BadMagic => write!(f, "not a file system image"),
+            ImageError::UnsupportedVersion(version) => write!(
+                f,
+                "image version {} is not supported (supported versions 1 to {})",
+                version, IMAGE_VERSION
+            ),
+            ImageError::Truncated => write!(f, "image is truncated"),
+            ImageError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "checksum mismatch: expected {:08x}, found {:08x}",
+                expected, found
+            ),
+            ImageError::Corrupted(reason) => write!(f, "image is corrupted: {}", reason),
+            ImageError::TooLarge(reason) => write!(f, "cannot save the image: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+impl From<io::Error> for ImageError {
+    fn from(e: io::Error) -> Self {
+        ImageError::Io(e)
+    }
+}
+
+impl FileSystem {
+    pub fn to_image(&self) -> Result<Vec<u8>, ImageError> {
+        let mut payload = Vec::new();
+        write_dir(&mut payload, &self.root, 0)?;
+
+        let mut image = Vec::with_capacity(HEADER_LEN + payload.len());
+        image.extend_from_slice(MAGIC);
+        image.extend_from_slice(&IMAGE_VERSION.to_le_bytes());
+        image.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+        image.extend_from_slice(&crc32(&payload).to_le_bytes());
+        image.extend_from_slice(&payload);
+        Ok(image)
+    }
+
+    pub fn from_image(image: &[u8]) -> Result<Self, ImageError> {
+        let mut reader = Reader { buf: image };
+        if reader.bytes(MAGIC.len())? != MAGIC {
+            return Err(ImageError::BadMagic);
+        }
+
+        let version = reader.u16()?;
+        // la versione 0 non è mai esistita
+        if version == 0 || version > IMAGE_VERSION {
+            return Err(ImageError::UnsupportedVersion(version));
+        }
+
+        let len = reader.u64()? as usize;
+        let expected = reader.u32()?;
+        let payload = reader.bytes(len)?;
+        if !reader.buf.is_empty() {
+            return Err(ImageError::Corrupted("trailing bytes".to_string()));
+        }
+
+        let found = crc32(payload);
+        if found != expected {
+            return Err(ImageError::ChecksumMismatch { expected, found });
+        }
+
+        let mut reader = Reader { buf: payload };
+        let root = match read_node(&mut reader, 0)? {
+            Node::Dir(dir) => dir,
+            Node::File(_) => return Err(ImageError::Corrupted("root is a file".to_string())),
+        };
+        if !reader.buf.is_empty() {
+            return Err(ImageError::Corrupted(
+                "trailing bytes after the root directory".to_string(),
+            ));
+        }
+        Ok(FileSystem { root })
+    }
+
+    pub fn save_image(&self, path: &str) -> Result<(), ImageError> {
+        fs::write(path, self.to_image()?)?;
+        Ok(())
+    }
+
+    pub fn load_image(path: &str) -> Result<Self, ImageError> {
+        FileSystem::from_image(&fs::read(path)?)
+    }
+}
+
+fn write_name(out: &mut Vec<u8>, name: &str) -> Result<(), ImageError> {
+    let len = u16::try_from(name.len()).map_err(|_| {
+        ImageError::TooLarge(format!(
+            "name of {} bytes is longer than {}",
+            name.len(),
+            u16::MAX
+        ))
+    })?;
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(name.as_bytes());
+    Ok(())
+}
+
+fn write_dir(out: &mut Vec<u8>, dir: &Dir, depth: usize) -> Result<(), ImageError> {
+    if depth > MAX_DEPTH {
+        return Err(ImageError::TooLarge(format!(
+            "directories nested deeper than {}",
+            MAX_DEPTH
+        )));
+    }
+    out.push(TAG_DIR);
+    write_name(out, &dir.name)?;
+    out.extend_from_slice(&dir.creation_time.to_le_bytes());
+    let count = u32::try_from(dir.children.len())
+        .map_err(|_| ImageError::TooLarge(format!("{}: too many children", dir.name)))?;
+    out.extend_from_slice(&count.to_le_bytes());
+    for node in &dir.children {
+        match node {
+            Node::Dir(sub) => write_dir(out, sub, depth + 1)?,
+            Node::File(file) => write_file(out, file)?,
+        }
+    }
+    Ok(())
+}
+
+fn write_file(out: &mut Vec<u8>, file: &File) -> Result<(), ImageError> {
+    out.push(TAG_FILE);
+    write_name(out, &file.name)?;
+    out.extend_from_slice(&file.creation_time.to_le_bytes());
+    out.push(match file.type_ {
+        FileType::Text => 0,
+        FileType::Binary => 1,
+    });
+    // il contenuto è al massimo MAX_CONTENT byte
+    out.extend_from_slice(&(file.content.len() as u32).to_le_bytes());
+    out.extend_from_slice(&file.content);
+    Ok(())
+}
+
+fn read_node(reader: &mut Reader, depth: usize) -> Result<Node, ImageError> {
+    if depth > MAX_DEPTH {
+        return Err(ImageError::Corrupted(format!(
+            "directories nested deeper than {}",
+            MAX_DEPTH
+        )));
+    }
+    let tag = reader.u8()?;
+    let len = reader.u16()? as usize;
+    let name = String::from_utf8(reader.bytes(len)?.to_vec())
+        .map_err(|_| ImageError::Corrupted("name is not valid UTF-8".to_string()))?;
+    let creation_time = reader.u64()?;
+
+    match tag {
+        TAG_DIR => {
+            let count = reader.u32()?;
+            let mut dir = Dir::new(name);
+            dir.creation_time = creation_time;
+            for _ in 0..count {
+                let child = read_node(reader, depth + 1)?;
+                if !is_valid_name(child.name()) {
+                    return Err(ImageError::Corrupted(format!(
+                        "{:?}: invalid name",
+                        child.name()
+                    )));
+                }
+                dir.children.push(child);
+            }
+            Ok(Node::Dir(dir))
+        }
+        TAG_FILE => {
+            let type_ = match reader.u8()? {
+                0 => FileType::Text,
+                1 => FileType::Binary,
+                other => {
+                    return Err(ImageError::Corrupted(format!(
+                        "unknown file type {}",
+                        other
+                    )))
+                }
+            };
+            let len = reader.u32()? as usize;
+            if len > MAX_CONTENT {
+                return Err(ImageError::Corrupted(format!(
+                    "{}: content longer than {} bytes",
+                    name, MAX_CONTENT
+                )));
+            }
+
+            let mut file = File::new(name, type_);
+            file.creation_time = creation_time;
+            file.content = reader.bytes(len)?.to_vec();
+            Ok(Node::File(file))
+        }
+        other => Err(ImageError::Corrupted(format!("unknown node tag {}", other))),
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], ImageError> {
+        if self.buf.len() < len {
+            return Err(ImageError::Truncated);
+        }
+        let (head, tail) = self.buf.split_at(len);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, ImageError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, ImageError> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, ImageError> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, ImageError> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+}
+
+// CRC-32 (IEEE 802.3), calcolato bit a bit
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod test {
+    use super::{crc32, ImageError, HEADER_LEN, IMAGE_VERSION, MAGIC, MAX_DEPTH};
+    use crate::{File, FileSystem, FileType, Node};
+
+    fn sample() -> FileSystem {
+        let mut fs = FileSystem::new();
+        fs.mk_dir("a/b").unwrap();
+        fs.new_file("a/b/f.txt", FileType::Text)
+            .unwrap()
+            .set_content(b"ciao");
+        fs.new_file("g.bin", FileType::Binary)
+            .unwrap()
+            .set_content(&[0, 255]);
+        fs
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn round_trip() {
+        let fs = sample();
+        let loaded = FileSystem::from_image(&fs.to_image().unwrap()).unwrap();
+        let file = loaded.get_file("a/b/f.txt").unwrap();
+        assert_eq!(file.content(), b"ciao");
+        assert_eq!(
+            file.creation_time(),
+            fs.get_file("a/b/f.txt").unwrap().creation_time()
+        );
+        assert_eq!(
+            loaded.get_file("g.bin").unwrap().file_type(),
+            &FileType::Binary
+        );
+        assert_eq!(loaded.to_image().unwrap(), fs.to_image().unwrap());
+    }
+
+    #[test]
+    fn truncated_image() {
+        let image = sample().to_image().unwrap();
+        for len in [0, 3, HEADER_LEN - 1, image.len() - 1] {
+            assert!(matches!(
+                FileSystem::from_image(&image[..len]),
+                Err(ImageError::Truncated)
+            ));
+        }
+    }
+
+    #[test]
+    fn newer_version_and_bad_checksum() {
+        let mut image = sample().to_image().unwrap();
+        let last = image.len() - 1;
+        image[last] ^= 0xFF;
+        assert!(matches!(
+            FileSystem::from_image(&image),
+            Err(ImageError::ChecksumMismatch { .. })
+        ));
+
+        image[4..6].copy_from_slice(&(IMAGE_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            FileSystem::from_image(&image),
+            Err(ImageError::UnsupportedVersion(v)) if v == IMAGE_VERSION + 1
+        ));
+        image[4..6].copy_from_slice(&0u16.to_le_bytes());
+        assert!(matches!(
+            FileSystem::from_image(&image),
+            Err(ImageError::UnsupportedVersion(0))
+        ));
+
+        assert!(matches!(
+            FileSystem::from_image(b"nope, not an image"),
+            Err(ImageError::BadMagic)
+        ));
+    }
+
+    #[test]
+    fn long_names_and_trailing_bytes() {
+        let mut fs = FileSystem::new();
+        fs.new_file(&"x".repeat(u16::MAX as usize + 1), FileType::Text)
+            .unwrap();
+        assert!(matches!(fs.to_image(), Err(ImageError::TooLarge(_))));
+
+        // byte in più dopo la radice, con lunghezza e checksum aggiornati
+        let mut image = sample().to_image().unwrap();
+        image.push(0);
+        let payload_len = (image.len() - HEADER_LEN) as u64;
+        image[6..14].copy_from_slice(&payload_len.to_le_bytes());
+        let crc = crc32(&image[HEADER_LEN..]);
+        image[14..18].copy_from_slice(&crc.to_le_bytes());
+        assert!(matches!(
+            FileSystem::from_image(&image),
+            Err(ImageError::Corrupted(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_names_are_rejected() {
+        for name in ["..", "a/b"] {
+            let mut fs = sample();
+            fs.root
+                .children
+                .push(Node::File(File::new(name.to_string(), FileType::Text)));
+            assert!(matches!(
+                FileSystem::from_image(&fs.to_image().unwrap()),
+                Err(ImageError::Corrupted(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn deep_nesting_is_rejected() {
+        // diecimila cartelle annidate, ognuna con un solo figlio
+        let mut payload = Vec::new();
+        for _ in 0..10_000 {
+            payload.push(0);
+            payload.extend_from_slice(&1u16.to_le_bytes());
+            payload.push(b'd');
+            payload.extend_from_slice(&0u64.to_le_bytes());
+            payload.extend_from_slice(&1u32.to_le_bytes());
+        }
+        let mut image = MAGIC.to_vec();
+        image.extend_from_slice(&IMAGE_VERSION.to_le_bytes());
+        image.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+        image.extend_from_slice(&crc32(&payload).to_le_bytes());
+        image.extend_from_slice(&payload);
+        assert!(matches!(
+            FileSystem::from_image(&image),
+            Err(ImageError::Corrupted(_))
+        ));
+
+        let mut fs = FileSystem::new();
+        fs.mk_dir(&vec!["d"; MAX_DEPTH + 1].join("/")).unwrap();
+        assert!(matches!(fs.to_image(), Err(ImageError::TooLarge(_))));
+        let mut fs = FileSystem::new();
+        fs.mk_dir(&vec!["d"; MAX_DEPTH].join("/")).unwrap();
+        assert!(FileSystem::from_image(&fs.to_image().unwrap()).is_ok());
+    }
+}
@@ -1,17 +1,31 @@
-use std::any::Any;
-use std::fmt::{format, write, Display, Formatter};
+use std::fmt::{Display, Formatter};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-#[derive(Clone)]
-enum FileType {
+mod disk;
+mod image;
+mod render;
+mod search;
+mod shared;
+mod shell;
+
+pub use image::{ImageError, IMAGE_VERSION};
+pub use search::{MatchResult, NodeKind, Query, QueryStat};
+pub use shared::SharedFileSystem;
+pub use shell::Shell;
+
+// dimensione massima del contenuto di un file, il resto viene troncato
+pub const MAX_CONTENT: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FileType {
     Text,
     Binary,
 }
 
-#[derive(Clone)]
-struct File {
+#[derive(Clone, Debug)]
+pub struct File {
     name: String,
-    content: Vec<u8>, // max 1000 bytes, rest of the file truncated
+    content: Vec<u8>, // max MAX_CONTENT bytes, rest of the file truncated
     creation_time: u64,
     type_: FileType,
 }
@@ -21,16 +35,34 @@ impl File {
         File {
             name: param_name,
             content: Vec::new(),
-            creation_time: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .expect("Error Creation Time")
-                .as_secs() as u64,
+            creation_time: now(),
             type_: file_type,
         }
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+
+    pub fn set_content(&mut self, content: &[u8]) {
+        let len = content.len().min(MAX_CONTENT);
+        self.content = content[..len].to_vec();
+    }
+
+    pub fn creation_time(&self) -> u64 {
+        self.creation_time
+    }
+
+    pub fn file_type(&self) -> &FileType {
+        &self.type_
+    }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct Dir {
     name: String,
     creation_time: u64,
@@ -41,13 +73,34 @@ impl Dir {
     fn new(param_name: String) -> Self {
         Dir {
             name: param_name,
-            creation_time: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .expect("Error Creation Time")
-                .as_secs() as u64,
+            creation_time: now(),
             children: Vec::new(),
         }
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn creation_time(&self) -> u64 {
+        self.creation_time
+    }
+
+    pub fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn child(&self, name: &str) -> Option<&Node> {
+        self.children.iter().find(|node| node.name() == name)
+    }
+
+    fn child_mut(&mut self, name: &str) -> Option<&mut Node> {
+        self.children.iter_mut().find(|node| node.name() == name)
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.children.iter().position(|node| node.name() == name)
+    }
 }
 
 impl Display for Dir {
@@ -57,30 +110,77 @@ impl Display for Dir {
         }
         Ok(())
     }
-
 }
 
 impl Display for Node {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Node::File(file) => write!(f, "{}", file.name),
-            Node::Dir(dir) => write!(f, "{}", dir.name)
+            Node::Dir(dir) => write!(f, "{}", dir.name),
         }
     }
-
 }
 
-#[derive(Clone)]
-enum Node {
+#[derive(Clone, Debug)]
+pub enum Node {
     File(File),
     Dir(Dir),
 }
 
-#[derive(Clone)]
+impl Node {
+    pub fn name(&self) -> &str {
+        match self {
+            Node::File(file) => &file.name,
+            Node::Dir(dir) => &dir.name,
+        }
+    }
+
+    fn set_name(&mut self, name: String) {
+        match self {
+            Node::File(file) => file.name = name,
+            Node::Dir(dir) => dir.name = name,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FsError {
+    NotFound(String),
+    NotADirectory(String),
+    NotAFile(String),
+    AlreadyExists(String),
+    DirectoryNotEmpty(String),
+    InvalidPath(String),
+    InvalidQuery(String),
+}
+
+impl Display for FsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FsError::NotFound(path) => write!(f, "{}: no such file or directory", path),
+            FsError::NotADirectory(path) => write!(f, "{}: not a directory", path),
+            FsError::NotAFile(path) => write!(f, "{}: is a directory", path),
+            FsError::AlreadyExists(path) => write!(f, "{}: already exists", path),
+            FsError::DirectoryNotEmpty(path) => write!(f, "{}: directory not empty", path),
+            FsError::InvalidPath(path) => write!(f, "{}: invalid path", path),
+            FsError::InvalidQuery(query) => write!(f, "{}: invalid query", query),
+        }
+    }
+}
+
+impl std::error::Error for FsError {}
+
+#[derive(Clone, Debug)]
 pub struct FileSystem {
     pub root: Dir,
 }
 
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FileSystem {
     pub fn new() -> Self {
         FileSystem {
@@ -88,81 +188,366 @@ impl FileSystem {
         }
     }
 
-    pub fn mk_dir(&mut self, param_path: String) {
-        let mut path: Vec<String> = split_dir(param_path);  // separa ogni sotto path in un vettore di stringhe
+    // crea la cartella indicata e tutte quelle intermedie mancanti (come mkdir -p)
+    pub fn mk_dir(&mut self, param_path: &str) -> Result<(), FsError> {
+        let path = split_dir(param_path)?;
+        if path.is_empty() {
+            return Err(FsError::AlreadyExists("/".to_string()));
+        }
+
+        let mut dir = &mut self.root;
+        for (i, name) in path.iter().enumerate() {
+            let last = i == path.len() - 1;
+            if dir.child(name).is_none() {
+                dir.children.push(Node::Dir(Dir::new(name.clone())));
+            } else if last {
+                return Err(FsError::AlreadyExists(join_path(&path)));
+            }
 
-        let mut dir = Dir::new("".to_string());
+            dir = match dir.child_mut(name) {
+                Some(Node::Dir(sub)) => sub,
+                _ => return Err(FsError::NotADirectory(join_path(&path[..=i]))),
+            };
+        }
 
-        let dir_to_add = path.get(path.len()-1).unwrap().clone();
-        path.remove(path.len()-1);
+        Ok(())
+    }
 
-        if path.len() == 0 {
-            dir = Dir::new(dir_to_add.to_string());
-        } else {
-            for directory in path {
-                let _ = self.root.children.iter().map(|x| println!("{}", *x));
+    // rimuove una cartella solo se vuota
+    pub fn rm_dir(&mut self, param_path: &str) -> Result<(), FsError> {
+        self.remove_dir(param_path, false)
+    }
 
-                /*match self.root.children.iter().map(|&node| node.name == directory) {
-                    Some(result) => println!("{}", result),
-                    None => {
-                        println!("Errore");
-                        return;
-                    }
-                }*/
-            }
+    // rimuove una cartella e tutto il suo contenuto
+    pub fn rm_dir_all(&mut self, param_path: &str) -> Result<(), FsError> {
+        self.remove_dir(param_path, true)
+    }
+
+    pub fn new_file(
+        &mut self,
+        param_path: &str,
+        file_type: FileType,
+    ) -> Result<&mut File, FsError> {
+        let path = split_dir(param_path)?;
+        let (name, parent) = path
+            .split_last()
+            .ok_or_else(|| FsError::InvalidPath(param_path.to_string()))?;
+
+        let dir = self.get_dir_mut(parent)?;
+        if dir.child(name).is_some() {
+            return Err(FsError::AlreadyExists(join_path(&path)));
         }
 
-        println!("dirs: {:?}", dir.name);
-        self.root.children.push(Node::Dir(dir));
+        dir.children
+            .push(Node::File(File::new(name.clone(), file_type)));
+        match dir.children.last_mut() {
+            Some(Node::File(file)) => Ok(file),
+            _ => unreachable!(),
+        }
+    }
 
+    pub fn rm_file(&mut self, param_path: &str) -> Result<File, FsError> {
+        let path = split_dir(param_path)?;
+        let (name, parent) = path
+            .split_last()
+            .ok_or_else(|| FsError::NotAFile("/".to_string()))?;
 
+        let dir = self.get_dir_mut(parent)?;
+        let index = dir
+            .position(name)
+            .ok_or_else(|| FsError::NotFound(join_path(&path)))?;
 
+        match &dir.children[index] {
+            Node::File(_) => {}
+            Node::Dir(_) => return Err(FsError::NotAFile(join_path(&path))),
+        }
 
+        match dir.children.remove(index) {
+            Node::File(file) => Ok(file),
+            Node::Dir(_) => unreachable!(),
+        }
+    }
 
+    pub fn get_file(&self, param_path: &str) -> Result<&File, FsError> {
+        let path = split_dir(param_path)?;
+        let (name, parent) = path
+            .split_last()
+            .ok_or_else(|| FsError::NotAFile("/".to_string()))?;
 
+        match self.get_dir(parent)?.child(name) {
+            Some(Node::File(file)) => Ok(file),
+            Some(Node::Dir(_)) => Err(FsError::NotAFile(join_path(&path))),
+            None => Err(FsError::NotFound(join_path(&path))),
+        }
+    }
 
+    pub fn get_file_mut(&mut self, param_path: &str) -> Result<&mut File, FsError> {
+        let path = split_dir(param_path)?;
+        let (name, parent) = path
+            .split_last()
+            .ok_or_else(|| FsError::NotAFile("/".to_string()))?;
 
+        match self.get_dir_mut(parent)?.child_mut(name) {
+            Some(Node::File(file)) => Ok(file),
+            Some(Node::Dir(_)) => Err(FsError::NotAFile(join_path(&path))),
+            None => Err(FsError::NotFound(join_path(&path))),
+        }
+    }
 
+    pub fn read_dir(&self, param_path: &str) -> Result<&Dir, FsError> {
+        self.get_dir(&split_dir(param_path)?)
+    }
 
+    // sposta o rinomina un nodo; se la destinazione è una cartella esistente
+    // il nodo viene spostato al suo interno mantenendo il nome
+    pub fn mv(&mut self, from: &str, to: &str) -> Result<(), FsError> {
+        let (src, dst) = self.transfer_paths(from, to)?;
+        let (name, parent) = src.split_last().unwrap();
+        let dir = self.get_dir_mut(parent)?;
+        let index = dir.position(name).unwrap();
+        let node = dir.children.remove(index);
+        self.insert_node(&dst, node)
+    }
 
+    // copia un file o un'intera cartella
+    pub fn cp(&mut self, from: &str, to: &str) -> Result<(), FsError> {
+        let (src, dst) = self.transfer_paths(from, to)?;
+        let (name, parent) = src.split_last().unwrap();
+        let node = self.get_dir(parent)?.child(name).unwrap().clone();
+        self.insert_node(&dst, node)
+    }
 
+    // controlla sorgente e destinazione di mv/cp prima di modificare l'albero
+    fn transfer_paths(&self, from: &str, to: &str) -> Result<(Vec<String>, Vec<String>), FsError> {
+        let src = split_dir(from)?;
+        let (name, parent) = src
+            .split_last()
+            .ok_or_else(|| FsError::InvalidPath("/".to_string()))?;
+        if self.get_dir(parent)?.child(name).is_none() {
+            return Err(FsError::NotFound(join_path(&src)));
+        }
 
+        let mut dst = split_dir(to)?;
+        if self.get_dir(&dst).is_ok() {
+            dst.push(name.clone());
+        }
+        if dst.starts_with(&src) {
+            return Err(FsError::InvalidPath(join_path(&dst)));
+        }
 
+        let (new_name, new_parent) = dst.split_last().unwrap();
+        if self.get_dir(new_parent)?.child(new_name).is_some() {
+            return Err(FsError::AlreadyExists(join_path(&dst)));
+        }
+        Ok((src, dst))
+    }
 
+    fn insert_node(&mut self, path: &[String], mut node: Node) -> Result<(), FsError> {
+        let (name, parent) = path.split_last().unwrap();
+        node.set_name(name.clone());
+        self.get_dir_mut(parent)?.children.push(node);
+        Ok(())
+    }
 
-//        self.root.children.iter().find(|node| node)
+    fn remove_dir(&mut self, param_path: &str, recursive: bool) -> Result<(), FsError> {
+        let path = split_dir(param_path)?;
+        let (name, parent) = path
+            .split_last()
+            .ok_or_else(|| FsError::InvalidPath("/".to_string()))?;
+
+        let dir = self.get_dir_mut(parent)?;
+        let index = dir
+            .position(name)
+            .ok_or_else(|| FsError::NotFound(join_path(&path)))?;
+
+        match &dir.children[index] {
+            Node::File(_) => return Err(FsError::NotADirectory(join_path(&path))),
+            Node::Dir(sub) if !recursive && !sub.children.is_empty() => {
+                return Err(FsError::DirectoryNotEmpty(join_path(&path)))
+            }
+            Node::Dir(_) => {}
+        }
 
-        //println!("{:?}", self.root.children.iter().find(|&x| *x.name == path_separeted[0]));
+        dir.children.remove(index);
+        Ok(())
+    }
 
-        // se il vettore ha almeno un elemento inizializza la cartella padre dir
-        /*if let Some(result) = path_separeted.get(0) {
-            dir = Dir::new(result.to_string())
-        } else {
-            println!("{}", "Error path");
-            return dir;
+    // scende lungo il percorso, ogni componente deve essere una cartella
+    fn get_dir(&self, path: &[String]) -> Result<&Dir, FsError> {
+        let mut dir = &self.root;
+        for (i, name) in path.iter().enumerate() {
+            dir = match dir.child(name) {
+                Some(Node::Dir(sub)) => sub,
+                Some(Node::File(_)) => return Err(FsError::NotADirectory(join_path(&path[..=i]))),
+                None => return Err(FsError::NotFound(join_path(&path[..=i]))),
+            };
         }
+        Ok(dir)
+    }
 
-        // inserisce ogni sottocartella nella cartella padre dir
-        for sub in path_separeted {
-            let sub_dir = Dir::new(sub);
-            dir.children.push(Node::Dir(sub_dir));
+    fn get_dir_mut(&mut self, path: &[String]) -> Result<&mut Dir, FsError> {
+        let mut dir = &mut self.root;
+        for (i, name) in path.iter().enumerate() {
+            dir = match dir.child_mut(name) {
+                Some(Node::Dir(sub)) => sub,
+                Some(Node::File(_)) => return Err(FsError::NotADirectory(join_path(&path[..=i]))),
+                None => return Err(FsError::NotFound(join_path(&path[..=i]))),
+            };
         }
-
-        // inserisce la cartella dir con le sue sottocartelle nel filesystem
-        self.root.children.push(Node::Dir(dir.clone()));
-        return dir;*/
+        Ok(dir)
     }
 }
 
-fn split_dir(path: String) -> Vec<String> {
-    let mut result: Vec<String> = path
-        .split("/")
-        .filter(|&s| !s.is_empty())
-        .map(|s| s.trim().to_owned())
-        .collect();
-    //result = result.iter().map(|s| format!("{}{}", s, "/")).collect();
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Error Creation Time")
+        .as_secs()
+}
+
+// nome ammesso per un file o una cartella: non vuoto, diverso da "." e ".." e
+// senza separatori, così non può uscire dalla radice quando viene scritto su disco
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name
+            .chars()
+            .any(|c| c == '/' || c == '\0' || std::path::is_separator(c))
+}
 
-    println!("{:?}", result);
+// separa ogni sotto path in un vettore di stringhe; ".." non è un nome valido
+// (i percorsi relativi vanno risolti prima, come fa la shell)
+fn split_dir(path: &str) -> Result<Vec<String>, FsError> {
+    path.split('/')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty() && *s != ".")
+        .map(|s| match is_valid_name(s) {
+            true => Ok(s.to_owned()),
+            false => Err(FsError::InvalidPath(path.to_string())),
+        })
+        .collect()
+}
 
-    return result;
+fn join_path(path: &[String]) -> String {
+    format!("/{}", path.join("/"))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{FileSystem, FileType, FsError};
+
+    #[test]
+    fn mk_dir_creates_intermediate_dirs() {
+        let mut fs = FileSystem::new();
+        fs.mk_dir("a/b/c").unwrap();
+        fs.mk_dir("/a/b/d/").unwrap();
+        assert_eq!(
+            fs.mk_dir("a/b"),
+            Err(FsError::AlreadyExists("/a/b".to_string()))
+        );
+        fs.new_file("/a/b/c/f.txt", FileType::Text).unwrap();
+        assert_eq!(fs.get_file("a/b/c/f.txt").unwrap().name(), "f.txt");
+    }
+
+    #[test]
+    fn mk_dir_through_file_fails() {
+        let mut fs = FileSystem::new();
+        fs.new_file("f", FileType::Binary).unwrap();
+        assert_eq!(
+            fs.mk_dir("f/a"),
+            Err(FsError::NotADirectory("/f".to_string()))
+        );
+    }
+
+    #[test]
+    fn dot_dot_is_not_a_name() {
+        let mut fs = FileSystem::new();
+        fs.mk_dir("a").unwrap();
+        assert_eq!(
+            fs.mk_dir("a/.."),
+            Err(FsError::InvalidPath("a/..".to_string()))
+        );
+        assert_eq!(
+            fs.new_file("/a/../f", FileType::Text).unwrap_err(),
+            FsError::InvalidPath("/a/../f".to_string())
+        );
+        assert!(fs.mv("a", "..").is_err());
+        assert_eq!(fs.read_dir("a").unwrap().children().len(), 0);
+        assert_eq!(fs.root.children().len(), 1);
+    }
+
+    #[test]
+    fn rm_dir_only_when_empty() {
+        let mut fs = FileSystem::new();
+        fs.mk_dir("a/b").unwrap();
+        assert_eq!(
+            fs.rm_dir("a"),
+            Err(FsError::DirectoryNotEmpty("/a".to_string()))
+        );
+        fs.rm_dir("a/b").unwrap();
+        fs.rm_dir("a").unwrap();
+        assert!(fs.root.children().is_empty());
+
+        fs.mk_dir("x/y/z").unwrap();
+        fs.rm_dir_all("x").unwrap();
+        assert_eq!(fs.rm_dir("x"), Err(FsError::NotFound("/x".to_string())));
+    }
+
+    #[test]
+    fn file_errors() {
+        let mut fs = FileSystem::new();
+        fs.mk_dir("a").unwrap();
+        fs.new_file("a/f", FileType::Text).unwrap();
+        assert_eq!(
+            fs.new_file("a/f", FileType::Text).unwrap_err(),
+            FsError::AlreadyExists("/a/f".to_string())
+        );
+        assert_eq!(
+            fs.new_file("b/f", FileType::Text).unwrap_err(),
+            FsError::NotFound("/b".to_string())
+        );
+        assert_eq!(
+            fs.get_file("a").unwrap_err(),
+            FsError::NotAFile("/a".to_string())
+        );
+        assert_eq!(
+            fs.rm_dir("a/f"),
+            Err(FsError::NotADirectory("/a/f".to_string()))
+        );
+        assert_eq!(fs.rm_file("a/f").unwrap().name(), "f");
+        assert_eq!(
+            fs.get_file_mut("a/f").unwrap_err(),
+            FsError::NotFound("/a/f".to_string())
+        );
+    }
+
+    #[test]
+    fn mv_and_cp() {
+        let mut fs = FileSystem::new();
+        fs.mk_dir("a/b").unwrap();
+        fs.mk_dir("c").unwrap();
+        fs.new_file("a/b/f", FileType::Text)
+            .unwrap()
+            .set_content(b"ciao");
+
+        fs.cp("a", "c").unwrap();
+        fs.mv("a/b/f", "a/g").unwrap();
+        assert_eq!(fs.get_file("a/g").unwrap().content(), b"ciao");
+        assert_eq!(fs.get_file("c/a/b/f").unwrap().content(), b"ciao");
+        assert_eq!(
+            fs.get_file("a/b/f").unwrap_err(),
+            FsError::NotFound("/a/b/f".to_string())
+        );
+
+        assert_eq!(
+            fs.mv("a", "a/b"),
+            Err(FsError::InvalidPath("/a/b/a".to_string()))
+        );
+        assert_eq!(
+            fs.cp("a/g", "c/a/b/f"),
+            Err(FsError::AlreadyExists("/c/a/b/f".to_string()))
+        );
+        assert_eq!(fs.mv("x", "c"), Err(FsError::NotFound("/x".to_string())));
+    }
 }
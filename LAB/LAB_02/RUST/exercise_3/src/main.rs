@@ -1,12 +1,52 @@
+use exercise_3::{FileSystem, Shell};
+use std::env;
+use std::io::{self, IsTerminal};
 
-
-mod lib;
-
+// uso: exercise_3 [--tree | --json] [cartella]
+// senza --tree/--json avvia la shell interattiva che legge i comandi da stdin
 fn main() {
-    let mut file_system = lib::FileSystem::new();
-    file_system.mk_dir("a/".to_string());
-    file_system.mk_dir("a/b/".to_string());
-    file_system.mk_dir("a/b/c/".to_string());
+    let mut format = "shell";
+    let mut path = None;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--tree" => format = "tree",
+            "--json" => format = "json",
+            _ if arg.starts_with("--") => {
+                eprintln!("unknown option {}", arg);
+                eprintln!("usage: exercise_3 [--tree | --json] [directory]");
+                std::process::exit(2);
+            }
+            _ => path = Some(arg),
+        }
+    }
+
+    // se viene passata una cartella la si carica da disco, altrimenti si parte da un albero vuoto
+    let file_system = match path {
+        Some(path) => match FileSystem::from_dir(&path) {
+            Ok(file_system) => file_system,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                std::process::exit(1);
+            }
+        },
+        None => FileSystem::new(),
+    };
 
-    println!("{}", file_system.root);
+    match format {
+        "tree" => print!("{}", file_system.render_tree()),
+        "json" => println!("{}", file_system.to_json()),
+        _ => {
+            let stdin = io::stdin();
+            let interactive = stdin.is_terminal();
+            let mut shell = Shell::new(file_system);
+            match shell.run(stdin.lock(), io::stdout(), interactive) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,191 @@
+use std::fmt::Write;
+
+use crate::{Dir, FileSystem, FileType, Node};
+
+impl FileSystem {
+    // rappresentazione indentata in stile `tree`, con dimensione e data di creazione
+    pub fn render_tree(&self) -> String {
+        let mut out = format!(
+            "{} [{}]\n",
+            self.root.name,
+            format_time(self.root.creation_time)
+        );
+        render_dir(&mut out, &self.root, "");
+
+        let (dirs, files) = count(&self.root);
+        let _ = writeln!(out, "\n{} directories, {} files", dirs, files);
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        json_dir(&mut out, &self.root);
+        out
+    }
+}
+
+fn render_dir(out: &mut String, dir: &Dir, prefix: &str) {
+    for (i, node) in dir.children.iter().enumerate() {
+        let last = i == dir.children.len() - 1;
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+
+        match node {
+            Node::Dir(sub) => {
+                let _ = writeln!(
+                    out,
+                    "{}{}{}/ [{}]",
+                    prefix,
+                    branch,
+                    sub.name,
+                    format_time(sub.creation_time)
+                );
+                render_dir(out, sub, &format!("{}{}", prefix, indent));
+            }
+            Node::File(file) => {
+                let _ = writeln!(
+                    out,
+                    "{}{}{} [{} B, {}]",
+                    prefix,
+                    branch,
+                    file.name,
+                    file.content.len(),
+                    format_time(file.creation_time)
+                );
+            }
+        }
+    }
+}
+
+fn count(dir: &Dir) -> (usize, usize) {
+    dir.children
+        .iter()
+        .fold((0, 0), |(dirs, files), node| match node {
+            Node::Dir(sub) => {
+                let (d, f) = count(sub);
+                (dirs + 1 + d, files + f)
+            }
+            Node::File(_) => (dirs, files + 1),
+        })
+}
+
+fn json_dir(out: &mut String, dir: &Dir) {
+    let _ = write!(
+        out,
+        "{{\"name\":{},\"type\":\"dir\",\"creation_time\":{},\"children\":[",
+        json_string(&dir.name),
+        dir.creation_time
+    );
+    for (i, node) in dir.children.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        match node {
+            Node::Dir(sub) => json_dir(out, sub),
+            Node::File(file) => {
+                let type_ = match file.type_ {
+                    FileType::Text => "text",
+                    FileType::Binary => "binary",
+                };
+                let _ = write!(
+                    out,
+                    "{{\"name\":{},\"type\":\"{}\",\"creation_time\":{},\"size\":{}}}",
+                    json_string(&file.name),
+                    type_,
+                    file.creation_time,
+                    file.content.len()
+                );
+            }
+        }
+    }
+    out.push_str("]}");
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// secondi dal 1970 in "AAAA-MM-GG hh:mm:ss" (UTC)
+fn format_time(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // algoritmo "civil from days" di Howard Hinnant
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format_time, json_string};
+    use crate::{FileSystem, FileType};
+
+    #[test]
+    fn time_format() {
+        assert_eq!(format_time(0), "1970-01-01 00:00:00");
+        assert_eq!(format_time(951_782_400), "2000-02-29 00:00:00");
+        assert_eq!(format_time(1_700_000_000), "2023-11-14 22:13:20");
+    }
+
+    #[test]
+    fn tree_and_json() {
+        let mut fs = FileSystem::new();
+        fs.mk_dir("a/b").unwrap();
+        fs.new_file("a/f.txt", FileType::Text)
+            .unwrap()
+            .set_content(b"ciao");
+        fs.new_file("g\"x", FileType::Binary).unwrap();
+
+        let tree = fs.render_tree();
+        let lines: Vec<&str> = tree.lines().collect();
+        assert!(lines[1].starts_with("├── a/ ["));
+        assert!(lines[2].starts_with("│   ├── b/ ["));
+        assert!(lines[3].starts_with("│   └── f.txt [4 B, "));
+        assert!(lines[4].starts_with("└── g\"x [0 B, "));
+        assert_eq!(lines[6], "2 directories, 2 files");
+
+        let json = fs.to_json();
+        assert!(json.starts_with("{\"name\":\"/\",\"type\":\"dir\""));
+        assert!(json.contains("{\"name\":\"b\",\"type\":\"dir\""));
+        assert!(json.contains("\"name\":\"f.txt\",\"type\":\"text\""));
+        assert!(json.contains("\"size\":4}"));
+        assert!(json.contains(&json_string("g\"x")));
+        assert!(json.ends_with("]}"));
+    }
+}
@@ -0,0 +1,241 @@
+use std::str::FromStr;
+
+use crate::{FileSystem, FileType, FsError, Node};
+
+// singola condizione di ricerca nella forma "chiave:valore"
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Query {
+    Name(String),    // name:*.txt, supporta i caratteri jolly * e ?
+    Content(String), // content:testo
+    Larger(usize),   // larger:500, dimensione del contenuto in byte
+    Smaller(usize),  // smaller:10
+    Newer(u64),      // newer:<epoch>, secondi dal 1970
+    Older(u64),      // older:<epoch>
+    Type(NodeKind),  // type:text | type:binary | type:dir
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    Text,
+    Binary,
+    Dir,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryStat {
+    pub query: String,
+    pub matches: usize,
+}
+
+// percorsi dei nodi che soddisfano tutte le query e, per ogni query,
+// quanti nodi la soddisfano presa da sola
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MatchResult {
+    pub paths: Vec<String>,
+    pub stats: Vec<QueryStat>,
+}
+
+impl FromStr for Query {
+    type Err = FsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || FsError::InvalidQuery(s.to_string());
+        let (key, value) = s.split_once(':').ok_or_else(invalid)?;
+
+        let query = match key {
+            "name" => Query::Name(value.to_string()),
+            "content" => Query::Content(value.to_string()),
+            "larger" => Query::Larger(value.parse().map_err(|_| invalid())?),
+            "smaller" => Query::Smaller(value.parse().map_err(|_| invalid())?),
+            "newer" => Query::Newer(value.parse().map_err(|_| invalid())?),
+            "older" => Query::Older(value.parse().map_err(|_| invalid())?),
+            "type" => Query::Type(match value {
+                "text" => NodeKind::Text,
+                "binary" => NodeKind::Binary,
+                "dir" => NodeKind::Dir,
+                _ => return Err(invalid()),
+            }),
+            _ => return Err(invalid()),
+        };
+        Ok(query)
+    }
+}
+
+impl Query {
+    pub fn matches(&self, node: &Node) -> bool {
+        match (self, node) {
+            (Query::Name(pattern), _) => glob_match(pattern.as_bytes(), node.name().as_bytes()),
+            (Query::Newer(time), Node::File(file)) => file.creation_time > *time,
+            (Query::Newer(time), Node::Dir(dir)) => dir.creation_time > *time,
+            (Query::Older(time), Node::File(file)) => file.creation_time < *time,
+            (Query::Older(time), Node::Dir(dir)) => dir.creation_time < *time,
+            (Query::Type(kind), Node::File(file)) => matches!(
+                (kind, &file.type_),
+                (NodeKind::Text, FileType::Text) | (NodeKind::Binary, FileType::Binary)
+            ),
+            (Query::Type(kind), Node::Dir(_)) => *kind == NodeKind::Dir,
+            (Query::Content(text), Node::File(file)) => {
+                text.is_empty()
+                    || file
+                        .content
+                        .windows(text.len())
+                        .any(|window| window == text.as_bytes())
+            }
+            (Query::Larger(size), Node::File(file)) => file.content.len() > *size,
+            (Query::Smaller(size), Node::File(file)) => file.content.len() < *size,
+            // contenuto e dimensione hanno senso solo per i file
+            (_, Node::Dir(_)) => false,
+        }
+    }
+}
+
+impl FileSystem {
+    pub fn search(&self, queries: &[&str]) -> Result<MatchResult, FsError> {
+        let parsed = queries
+            .iter()
+            .map(|query| query.parse::<Query>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut result = MatchResult {
+            paths: Vec::new(),
+            stats: queries
+                .iter()
+                .map(|query| QueryStat {
+                    query: query.to_string(),
+                    matches: 0,
+                })
+                .collect(),
+        };
+
+        let mut stack: Vec<(String, &Node)> = self
+            .root
+            .children
+            .iter()
+            .rev()
+            .map(|node| (format!("/{}", node.name()), node))
+            .collect();
+
+        while let Some((path, node)) = stack.pop() {
+            let mut all = true;
+            for (query, stat) in parsed.iter().zip(result.stats.iter_mut()) {
+                if query.matches(node) {
+                    stat.matches += 1;
+                } else {
+                    all = false;
+                }
+            }
+
+            if let Node::Dir(dir) = node {
+                for child in dir.children.iter().rev() {
+                    stack.push((format!("{}/{}", path, child.name()), child));
+                }
+            }
+
+            if all {
+                result.paths.push(path);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+// confronto con due indici: quando un carattere non corrisponde si torna all'ultimo
+// '*' facendogli coprire un carattere in più, quindi O(n·m) anche con tanti '*'
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // posizione dopo l'ultimo '*' visto e carattere del nome da cui riprendere
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                p += 1;
+                star = Some((p, n));
+            }
+            Some(&c) if c == b'?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((after, from)) => {
+                    p = after;
+                    n = from + 1;
+                    star = Some((after, from + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{FileSystem, FileType, FsError};
+
+    fn sample() -> FileSystem {
+        let mut fs = FileSystem::new();
+        fs.mk_dir("docs/old").unwrap();
+        fs.new_file("docs/a.txt", FileType::Text)
+            .unwrap()
+            .set_content(&[b'a'; 600]);
+        fs.new_file("docs/old/b.txt", FileType::Text)
+            .unwrap()
+            .set_content(b"ciao mondo");
+        fs.new_file("img.png", FileType::Binary)
+            .unwrap()
+            .set_content(&[0, 1, 2]);
+        fs
+    }
+
+    #[test]
+    fn search_combines_queries() {
+        let fs = sample();
+        let result = fs.search(&["name:*.txt", "larger:500"]).unwrap();
+        assert_eq!(result.paths, vec!["/docs/a.txt"]);
+        assert_eq!(result.stats[0].matches, 2);
+        assert_eq!(result.stats[1].matches, 1);
+
+        let result = fs.search(&["content:mondo"]).unwrap();
+        assert_eq!(result.paths, vec!["/docs/old/b.txt"]);
+
+        let result = fs.search(&["type:binary", "smaller:10"]).unwrap();
+        assert_eq!(result.paths, vec!["/img.png"]);
+
+        let result = fs.search(&["type:dir", "newer:0"]).unwrap();
+        assert_eq!(result.paths, vec!["/docs", "/docs/old"]);
+    }
+
+    #[test]
+    fn invalid_query() {
+        let fs = sample();
+        assert_eq!(
+            fs.search(&["larger:tanto"]),
+            Err(FsError::InvalidQuery("larger:tanto".to_string()))
+        );
+        assert_eq!(
+            fs.search(&["colour:red"]),
+            Err(FsError::InvalidQuery("colour:red".to_string()))
+        );
+    }
+
+    #[test]
+    fn glob_patterns() {
+        use super::glob_match;
+        let matches = |pattern: &str, name: &str| glob_match(pattern.as_bytes(), name.as_bytes());
+        assert!(matches("*.txt", "a.txt"));
+        assert!(matches("a?c", "abc"));
+        assert!(matches("*", ""));
+        assert!(matches("a*b*c", "aXbYbZc"));
+        assert!(matches("**a", "a"));
+        assert!(!matches("a?c", "ac"));
+        assert!(!matches("*.txt", "a.txt.bak"));
+        assert!(!matches("", "a"));
+
+        // con il backtracking ricorsivo questo richiederebbe un tempo esponenziale
+        let name = "a".repeat(10_000);
+        assert!(!matches("*a*a*a*a*a*a*a*a*b", &name));
+        assert!(matches("*a*a*a*a*a*a*a*a*", &name));
+    }
+}
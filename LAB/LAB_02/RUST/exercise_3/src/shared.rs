@@ -0,0 +1,483 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::{join_path, now, split_dir, Dir, File, FileSystem, FileType, FsError, Node};
+
+// variante concorrente del FileSystem: ogni cartella ha il proprio RwLock sui figli
+// e ogni file il proprio Mutex, così le operazioni su /a non bloccano quelle su /b.
+// Durante la discesa lungo il percorso il lock di una cartella viene tenuto solo
+// il tempo di clonare l'Arc del figlio: chi arriva a una cartella mentre un altro
+// thread la rimuove se ne accorge dal flag `removed` dopo averne preso il lock, e
+// riparte dalla radice invece di scrivere in una cartella ormai staccata dall'albero.
+#[derive(Clone)]
+pub struct SharedFileSystem {
+    root: Arc<SharedDir>,
+}
+
+struct SharedDir {
+    name: String,
+    creation_time: u64,
+    children: RwLock<Vec<SharedNode>>,
+    // scritto tenendo il lock di `children`, quindi chi prende il lock in scrittura
+    // per aggiungere un figlio lo vede aggiornato; una volta vero resta vero
+    removed: AtomicBool,
+}
+
+// il nome del file è copiato nel nodo per poter scorrere i figli di una cartella
+// senza prendere il lock dei singoli file
+#[derive(Clone)]
+enum SharedNode {
+    File(String, Arc<Mutex<File>>),
+    Dir(Arc<SharedDir>),
+}
+
+impl SharedDir {
+    fn new(name: String, creation_time: u64) -> Self {
+        SharedDir {
+            name,
+            creation_time,
+            children: RwLock::new(Vec::new()),
+            removed: AtomicBool::new(false),
+        }
+    }
+
+    fn child(&self, name: &str) -> Option<SharedNode> {
+        let children = self.children.read().unwrap();
+        children.iter().find(|node| node.name() == name).cloned()
+    }
+
+    fn is_removed(&self) -> bool {
+        self.removed.load(Ordering::Acquire)
+    }
+
+    // segna come rimossa la cartella e, con `recursive`, tutto il sotto albero; i lock
+    // si prendono dal padre verso i figli come nella discesa dei percorsi.
+    // Senza `recursive` non fa niente e restituisce false se la cartella non è vuota
+    fn mark_removed(&self, recursive: bool) -> bool {
+        // basta il lock in lettura per tenere fuori chi aggiunge figli
+        let children = self.children.read().unwrap();
+        if !recursive && !children.is_empty() {
+            return false;
+        }
+        for node in children.iter() {
+            if let SharedNode::Dir(sub) = node {
+                sub.mark_removed(true);
+            }
+        }
+        self.removed.store(true, Ordering::Release);
+        true
+    }
+}
+
+impl SharedNode {
+    fn name(&self) -> &str {
+        match self {
+            SharedNode::File(name, _) => name,
+            SharedNode::Dir(dir) => &dir.name,
+        }
+    }
+
+    fn from_node(node: Node) -> Self {
+        match node {
+            Node::File(file) => SharedNode::File(file.name.clone(), Arc::new(Mutex::new(file))),
+            Node::Dir(dir) => SharedNode::Dir(Arc::new(SharedDir::from(dir))),
+        }
+    }
+
+    fn to_node(&self) -> Node {
+        match self {
+            SharedNode::File(_, file) => Node::File(file.lock().unwrap().clone()),
+            SharedNode::Dir(dir) => Node::Dir(dir.to_dir()),
+        }
+    }
+}
+
+impl From<Dir> for SharedDir {
+    fn from(dir: Dir) -> Self {
+        let shared = SharedDir::new(dir.name, dir.creation_time);
+        *shared.children.write().unwrap() = dir
+            .children
+            .into_iter()
+            .map(SharedNode::from_node)
+            .collect();
+        shared
+    }
+}
+
+impl SharedDir {
+    fn to_dir(&self) -> Dir {
+        let mut dir = Dir::new(self.name.clone());
+        dir.creation_time = self.creation_time;
+        dir.children = self
+            .children
+            .read()
+            .unwrap()
+            .iter()
+            .map(SharedNode::to_node)
+            .collect();
+        dir
+    }
+}
+
+impl From<FileSystem> for SharedFileSystem {
+    fn from(file_system: FileSystem) -> Self {
+        SharedFileSystem {
+            root: Arc::new(SharedDir::from(file_system.root)),
+        }
+    }
+}
+
+impl Default for SharedFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SharedFileSystem {
+    pub fn new() -> Self {
+        SharedFileSystem::from(FileSystem::new())
+    }
+
+    // copia coerente per cartella (non globale) dell'albero corrente
+    pub fn snapshot(&self) -> FileSystem {
+        FileSystem {
+            root: self.root.to_dir(),
+        }
+    }
+
+    pub fn mk_dir(&self, param_path: &str) -> Result<(), FsError> {
+        let path = split_dir(param_path)?;
+        if path.is_empty() {
+            return Err(FsError::AlreadyExists("/".to_string()));
+        }
+
+        // se una cartella del percorso viene rimossa mentre la si attraversa si
+        // riparte dalla radice, ricreando le cartelle intermedie che mancano
+        'retry: loop {
+            let mut dir = self.root.clone();
+            for (i, name) in path.iter().enumerate() {
+                let last = i == path.len() - 1;
+                // il lock in scrittura serve solo se la cartella manca, e va ricontrollata
+                // perché un altro thread potrebbe averla creata nel frattempo
+                let node = match dir.child(name) {
+                    Some(_) if dir.is_removed() => continue 'retry,
+                    Some(_) if last => return Err(FsError::AlreadyExists(join_path(&path))),
+                    Some(node) => node,
+                    None => {
+                        let mut children = dir.children.write().unwrap();
+                        if dir.is_removed() {
+                            continue 'retry;
+                        }
+                        match children.iter().find(|node| node.name() == name) {
+                            Some(_) if last => {
+                                return Err(FsError::AlreadyExists(join_path(&path)))
+                            }
+                            Some(node) => node.clone(),
+                            None => {
+                                let sub =
+                                    SharedNode::Dir(Arc::new(SharedDir::new(name.clone(), now())));
+                                children.push(sub.clone());
+                                sub
+                            }
+                        }
+                    }
+                };
+
+                dir = match node {
+                    SharedNode::Dir(sub) => sub,
+                    SharedNode::File(..) => {
+                        return Err(FsError::NotADirectory(join_path(&path[..=i])))
+                    }
+                };
+            }
+
+            return Ok(());
+        }
+    }
+
+    pub fn rm_dir(&self, param_path: &str) -> Result<(), FsError> {
+        self.remove(param_path, |path, node| match node {
+            SharedNode::File(..) => Err(FsError::NotADirectory(path)),
+            SharedNode::Dir(dir) if !dir.mark_removed(false) => {
+                Err(FsError::DirectoryNotEmpty(path))
+            }
+            SharedNode::Dir(_) => Ok(()),
+        })
+    }
+
+    pub fn rm_dir_all(&self, param_path: &str) -> Result<(), FsError> {
+        self.remove(param_path, |path, node| match node {
+            SharedNode::File(..) => Err(FsError::NotADirectory(path)),
+            SharedNode::Dir(dir) => {
+                dir.mark_removed(true);
+                Ok(())
+            }
+        })
+    }
+
+    pub fn new_file(&self, param_path: &str, file_type: FileType) -> Result<(), FsError> {
+        let path = split_dir(param_path)?;
+        let (name, parent) = path
+            .split_last()
+            .ok_or_else(|| FsError::InvalidPath(param_path.to_string()))?;
+
+        self.with_children(parent, |children| {
+            if children.iter().any(|node| node.name() == name) {
+                return Err(FsError::AlreadyExists(join_path(&path)));
+            }
+            let file = File::new(name.clone(), file_type);
+            children.push(SharedNode::File(name.clone(), Arc::new(Mutex::new(file))));
+            Ok(())
+        })
+    }
+
+    pub fn rm_file(&self, param_path: &str) -> Result<File, FsError> {
+        let mut removed = None;
+        self.remove(param_path, |path, node| match node {
+            SharedNode::File(_, file) => {
+                removed = Some(file.lock().unwrap().clone());
+                Ok(())
+            }
+            SharedNode::Dir(_) => Err(FsError::NotAFile(path)),
+        })?;
+        Ok(removed.unwrap())
+    }
+
+    pub fn get_file(&self, param_path: &str) -> Result<File, FsError> {
+        self.with_file(param_path, |file| file.clone())
+    }
+
+    // esegue `f` tenendo il lock del solo file indicato
+    pub fn with_file<R>(
+        &self,
+        param_path: &str,
+        f: impl FnOnce(&mut File) -> R,
+    ) -> Result<R, FsError> {
+        let path = split_dir(param_path)?;
+        let (name, parent) = path
+            .split_last()
+            .ok_or_else(|| FsError::NotAFile("/".to_string()))?;
+
+        match self.get_dir(parent)?.child(name) {
+            Some(SharedNode::File(_, file)) => Ok(f(&mut file.lock().unwrap())),
+            Some(SharedNode::Dir(_)) => Err(FsError::NotAFile(join_path(&path))),
+            None => Err(FsError::NotFound(join_path(&path))),
+        }
+    }
+
+    // rimuove il nodo indicato se `check` lo consente, tenendo il lock in scrittura
+    // della sola cartella padre
+    fn remove(
+        &self,
+        param_path: &str,
+        mut check: impl FnMut(String, &SharedNode) -> Result<(), FsError>,
+    ) -> Result<(), FsError> {
+        let path = split_dir(param_path)?;
+        let (name, parent) = path
+            .split_last()
+            .ok_or_else(|| FsError::InvalidPath("/".to_string()))?;
+
+        self.with_children(parent, |children| {
+            let index = children
+                .iter()
+                .position(|node| node.name() == name)
+                .ok_or_else(|| FsError::NotFound(join_path(&path)))?;
+
+            check(join_path(&path), &children[index])?;
+            children.remove(index);
+            Ok(())
+        })
+    }
+
+    // esegue `f` sui figli della cartella tenendone il lock in scrittura; se nel
+    // frattempo la cartella è stata rimossa si risolve di nuovo il percorso
+    fn with_children<T>(
+        &self,
+        path: &[String],
+        f: impl FnOnce(&mut Vec<SharedNode>) -> Result<T, FsError>,
+    ) -> Result<T, FsError> {
+        loop {
+            let dir = self.get_dir(path)?;
+            let mut children = dir.children.write().unwrap();
+            if !dir.is_removed() {
+                return f(&mut children);
+            }
+        }
+    }
+
+    fn get_dir(&self, path: &[String]) -> Result<Arc<SharedDir>, FsError> {
+        let mut dir = self.root.clone();
+        for (i, name) in path.iter().enumerate() {
+            dir = match dir.child(name) {
+                Some(SharedNode::Dir(sub)) => sub,
+                Some(SharedNode::File(..)) => {
+                    return Err(FsError::NotADirectory(join_path(&path[..=i])))
+                }
+                None => return Err(FsError::NotFound(join_path(&path[..=i]))),
+            };
+        }
+        Ok(dir)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SharedFileSystem;
+    use crate::{FileType, FsError};
+    use std::thread;
+
+    const THREADS: usize = 8;
+    const FILES: usize = 200;
+
+    #[test]
+    fn concurrent_operations_do_not_lose_updates() {
+        let fs = SharedFileSystem::new();
+        fs.mk_dir("shared").unwrap();
+        fs.new_file("shared/counter", FileType::Binary).unwrap();
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let fs = fs.clone();
+                thread::spawn(move || {
+                    // tutti i thread creano la stessa catena di cartelle intermedie
+                    fs.mk_dir(&format!("common/deep/t{}", t)).unwrap();
+                    for i in 0..FILES {
+                        fs.new_file(&format!("common/deep/t{}/f{}", t, i), FileType::Text)
+                            .unwrap();
+                        fs.with_file("shared/counter", |file| {
+                            let mut bytes = [0u8; 8];
+                            bytes[..file.content().len()].copy_from_slice(file.content());
+                            let count = u64::from_le_bytes(bytes) + 1;
+                            file.set_content(&count.to_le_bytes());
+                        })
+                        .unwrap();
+                        if i % 2 == 0 {
+                            fs.rm_file(&format!("common/deep/t{}/f{}", t, i)).unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let counter = fs.get_file("shared/counter").unwrap();
+        let count = u64::from_le_bytes(counter.content().try_into().unwrap());
+        assert_eq!(count as usize, THREADS * FILES);
+
+        let snapshot = fs.snapshot();
+        let result = snapshot.search(&["name:f*", "type:text"]).unwrap();
+        assert_eq!(result.paths.len(), THREADS * FILES / 2);
+        assert_eq!(
+            snapshot.search(&["name:deep"]).unwrap().paths,
+            vec!["/common/deep"]
+        );
+    }
+
+    #[test]
+    fn create_and_remove_on_the_same_subtree() {
+        const CREATORS: usize = 4;
+        const REMOVERS: usize = 4;
+        let fs = SharedFileSystem::new();
+
+        // i file creati direttamente in a/b devono restare: rm_dir non rimuove
+        // cartelle piene e rm_dir_all tocca solo a/b/c
+        let creators: Vec<_> = (0..CREATORS)
+            .map(|t| {
+                let fs = fs.clone();
+                thread::spawn(move || {
+                    let mut created = Vec::new();
+                    for i in 0..FILES {
+                        let path = format!("a/b/t{}_{}", t, i);
+                        loop {
+                            match fs.mk_dir("a/b") {
+                                Ok(()) | Err(FsError::AlreadyExists(_)) => {}
+                                Err(e) => panic!("{}", e),
+                            }
+                            thread::yield_now();
+                            match fs.new_file(&path, FileType::Text) {
+                                Ok(()) => break,
+                                Err(FsError::NotFound(_)) => continue,
+                                Err(e) => panic!("{}", e),
+                            }
+                        }
+                        created.push(format!("/{}", path));
+                        let _ = fs.mk_dir(&format!("a/b/c/d{}/e{}", t, i));
+                        let _ = fs.new_file(&format!("a/b/c/d{}/e{}/f", t, i), FileType::Binary);
+                    }
+                    created
+                })
+            })
+            .collect();
+        let removers: Vec<_> = (0..REMOVERS)
+            .map(|_| {
+                let fs = fs.clone();
+                thread::spawn(move || {
+                    for _ in 0..FILES {
+                        let _ = fs.rm_dir("a/b");
+                        thread::yield_now();
+                        let _ = fs.rm_dir_all("a/b/c");
+                    }
+                })
+            })
+            .collect();
+
+        let mut created = Vec::new();
+        for handle in creators {
+            created.extend(handle.join().unwrap());
+        }
+        for handle in removers {
+            handle.join().unwrap();
+        }
+
+        let snapshot = fs.snapshot();
+        let mut found = snapshot.search(&["name:t*"]).unwrap().paths;
+        found.sort();
+        created.sort();
+        assert_eq!(found, created);
+    }
+
+    #[test]
+    fn removed_subtree_is_marked() {
+        let fs = SharedFileSystem::new();
+        fs.mk_dir("a/b/c").unwrap();
+        let path = crate::split_dir("a/b/c").unwrap();
+        // chi ha già risolto il percorso non deve più poterci scrivere
+        let stale = fs.get_dir(&path).unwrap();
+        fs.rm_dir_all("a").unwrap();
+        assert!(stale.is_removed());
+        assert_eq!(
+            fs.new_file("a/b/c/x", FileType::Text),
+            Err(FsError::NotFound("/a".to_string()))
+        );
+
+        fs.mk_dir("a/b").unwrap();
+        let stale = fs.get_dir(&path[..2]).unwrap();
+        fs.rm_dir("a/b").unwrap();
+        assert!(stale.is_removed());
+        fs.mk_dir("a/b/c").unwrap();
+        assert!(!fs.get_dir(&path).unwrap().is_removed());
+    }
+
+    #[test]
+    fn shared_errors() {
+        let fs = SharedFileSystem::new();
+        fs.mk_dir("a/b").unwrap();
+        assert_eq!(
+            fs.rm_dir("a"),
+            Err(FsError::DirectoryNotEmpty("/a".to_string()))
+        );
+        assert_eq!(
+            fs.new_file("a/b", FileType::Text),
+            Err(FsError::AlreadyExists("/a/b".to_string()))
+        );
+        assert_eq!(
+            fs.get_file("a/x").unwrap_err(),
+            FsError::NotFound("/a/x".to_string())
+        );
+        fs.rm_dir_all("a").unwrap();
+        assert!(fs.snapshot().root.children().is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+use std::io::{self, BufRead, Write};
+
+use crate::{FileSystem, FileType, Node};
+
+// interprete di comandi sul FileSystem in memoria; i percorsi relativi
+// vengono risolti a partire dalla cartella corrente
+pub struct Shell {
+    pub file_system: FileSystem,
+    cwd: Vec<String>,
+}
+
+impl Shell {
+    pub fn new(file_system: FileSystem) -> Self {
+        Shell {
+            file_system,
+            cwd: Vec::new(),
+        }
+    }
+
+    pub fn pwd(&self) -> String {
+        format!("/{}", self.cwd.join("/"))
+    }
+
+    // legge un comando per riga fino a EOF o `exit`; il prompt viene stampato
+    // solo se `prompt` è vero, così le sessioni da script restano pulite
+    pub fn run<R: BufRead, W: Write>(
+        &mut self,
+        input: R,
+        mut output: W,
+        prompt: bool,
+    ) -> io::Result<bool> {
+        let mut ok = true;
+        if prompt {
+            write!(output, "{}$ ", self.pwd())?;
+            output.flush()?;
+        }
+
+        for line in input.lines() {
+            let line = line?;
+            if matches!(line.trim(), "exit" | "quit") {
+                break;
+            }
+
+            match self.execute(&line) {
+                Ok(out) => write!(output, "{}", out)?,
+                Err(e) => {
+                    ok = false;
+                    writeln!(output, "{}", e)?;
+                }
+            }
+
+            if prompt {
+                write!(output, "{}$ ", self.pwd())?;
+                output.flush()?;
+            }
+        }
+        Ok(ok)
+    }
+
+    // esegue una singola riga e restituisce l'output da stampare
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let args: Vec<&str> = line.split_whitespace().collect();
+        let (command, args) = match args.split_first() {
+            Some((command, args)) => (*command, args),
+            None => return Ok(String::new()),
+        };
+        let fail = |e: crate::FsError| format!("{}: {}", command, e);
+
+        match (command, args) {
+            ("pwd", []) => Ok(format!("{}\n", self.pwd())),
+            ("ls", [] | [_]) => {
+                let path = self.resolve(args.first().copied().unwrap_or("."));
+                let dir = self.file_system.read_dir(&path).map_err(fail)?;
+                Ok(dir
+                    .children()
+                    .iter()
+                    .map(|node| match node {
+                        Node::Dir(sub) => format!("{}/\n", sub.name()),
+                        Node::File(file) => format!("{}\n", file.name()),
+                    })
+                    .collect())
+            }
+            ("cd", [] | [_]) => {
+                let path = self.resolve(args.first().copied().unwrap_or("/"));
+                self.file_system.read_dir(&path).map_err(fail)?;
+                self.cwd = crate::split_dir(&path).map_err(fail)?;
+                Ok(String::new())
+            }
+            ("mkdir", [_, ..]) => {
+                for arg in args {
+                    let path = self.resolve(arg);
+                    self.file_system.mk_dir(&path).map_err(fail)?;
+                }
+                Ok(String::new())
+            }
+            ("touch", [_, ..]) => {
+                for arg in args {
+                    let path = self.resolve(arg);
+                    if self.file_system.get_file(&path).is_err() {
+                        self.file_system
+                            .new_file(&path, FileType::Text)
+                            .map_err(fail)?;
+                    }
+                }
+                Ok(String::new())
+            }
+            ("cat", [_, ..]) => {
+                let mut out = String::new();
+                for arg in args {
+                    let file = self
+                        .file_system
+                        .get_file(&self.resolve(arg))
+                        .map_err(fail)?;
+                    out.push_str(&String::from_utf8_lossy(file.content()));
+                }
+                if !out.is_empty() && !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                Ok(out)
+            }
+            ("write", [file, ..]) => {
+                let path = self.resolve(file);
+                // il testo è tutto ciò che segue il nome del file
+                let text = line.trim_start()[command.len()..]
+                    .trim_start()
+                    .strip_prefix(file)
+                    .unwrap_or("")
+                    .trim_start();
+                let file = match self.file_system.get_file_mut(&path) {
+                    Ok(file) => file,
+                    Err(_) => self
+                        .file_system
+                        .new_file(&path, FileType::Text)
+                        .map_err(fail)?,
+                };
+                file.set_content(text.as_bytes());
+                Ok(String::new())
+            }
+            ("rm", ["-r", _, ..]) => {
+                let result = args[1..].iter().try_for_each(|arg| {
+                    let path = self.resolve(arg);
+                    match self.file_system.rm_file(&path) {
+                        Err(crate::FsError::NotAFile(_)) => self.file_system.rm_dir_all(&path),
+                        result => result.map(|_| ()),
+                    }
+                });
+                self.leave_removed_cwd();
+                result.map(|_| String::new()).map_err(fail)
+            }
+            ("rm", [_, ..]) => {
+                for arg in args {
+                    let path = self.resolve(arg);
+                    self.file_system.rm_file(&path).map_err(fail)?;
+                }
+                Ok(String::new())
+            }
+            ("mv", [from, to]) => {
+                let (from, to) = (self.resolve(from), self.resolve(to));
+                self.file_system.mv(&from, &to).map_err(fail)?;
+                self.leave_removed_cwd();
+                Ok(String::new())
+            }
+            ("cp", [from, to]) => {
+                let (from, to) = (self.resolve(from), self.resolve(to));
+                self.file_system.cp(&from, &to).map_err(fail)?;
+                Ok(String::new())
+            }
+            ("help", []) => Ok(HELP.to_string()),
+            (
+                "pwd" | "ls" | "cd" | "mkdir" | "touch" | "cat" | "write" | "rm" | "mv" | "cp"
+                | "help",
+                _,
+            ) => Err(format!("{}: wrong number of arguments", command)),
+            _ => Err(format!("{}: command not found", command)),
+        }
+    }
+
+    // trasforma un percorso relativo in assoluto gestendo "." e ".."
+    fn resolve(&self, path: &str) -> String {
+        let mut result = if path.starts_with('/') {
+            Vec::new()
+        } else {
+            self.cwd.clone()
+        };
+
+        for component in path.split('/') {
+            match component {
+                "" | "." => {}
+                ".." => {
+                    result.pop();
+                }
+                name => result.push(name.to_string()),
+            }
+        }
+        format!("/{}", result.join("/"))
+    }
+
+    // se la cartella corrente è stata rimossa o spostata si risale fino a una esistente
+    fn leave_removed_cwd(&mut self) {
+        while self.file_system.read_dir(&self.pwd()).is_err() {
+            self.cwd.pop();
+        }
+    }
+}
+
+const HELP: &str = "\
+pwd                  print the current directory
+ls [path]            list a directory
+cd [path]            change directory
+mkdir path...        create directories and missing parents
+touch path...        create empty text files
+cat path...          print file contents
+write path text      replace the content of a file
+rm [-r] path...      remove files (and directories with -r)
+mv from to           move or rename
+cp from to           copy files and directories
+exit                 leave the shell
+";
+
+#[cfg(test)]
+mod test {
+    use super::Shell;
+    use crate::FileSystem;
+
+    fn session(script: &str) -> (String, bool) {
+        let mut shell = Shell::new(FileSystem::new());
+        let mut output = Vec::new();
+        let ok = shell.run(script.as_bytes(), &mut output, false).unwrap();
+        (String::from_utf8(output).unwrap(), ok)
+    }
+
+    #[test]
+    fn scripted_session() {
+        let (output, ok) = session(
+            "mkdir docs/old tmp
+cd docs
+pwd
+write note.txt hello   world
+cat note.txt
+cp note.txt old
+cd old
+ls
+mv ../note.txt ../../tmp/renamed.txt
+cat /tmp/renamed.txt
+cd ..
+ls
+rm -r ../docs
+pwd
+ls /
+exit
+pwd
+",
+        );
+        assert!(ok);
+        assert_eq!(
+            output,
+            "/docs
+hello   world
+note.txt
+hello   world
+old/
+/
+tmp/
+"
+        );
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        let (output, ok) = session(
+            "cd nowhere
+touch f
+mkdir f
+rm f f
+frobnicate
+pwd x
+",
+        );
+        assert!(!ok);
+        assert_eq!(
+            output,
+            "cd: /nowhere: no such file or directory
+mkdir: /f: already exists
+rm: /f: no such file or directory
+frobnicate: command not found
+pwd: wrong number of arguments
+"
+        );
+    }
+}
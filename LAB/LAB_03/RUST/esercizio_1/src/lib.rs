@@ -0,0 +1,340 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::iter::FusedIterator;
+use std::ops::{Index, IndexMut};
+
+// cosa fare quando si inserisce in un buffer pieno
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    // l'inserimento fallisce e il valore viene restituito nell'errore
+    Reject,
+    // il valore più vecchio viene tolto per fare spazio
+    Overwrite,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BufferError<T> {
+    Full(T),
+}
+
+impl<T> Display for BufferError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BufferError::Full(_) => write!(f, "the buffer is full"),
+        }
+    }
+}
+
+impl<T: Debug> std::error::Error for BufferError<T> {}
+
+pub struct CircularBuffer<T> {
+    tape: Vec<Option<T>>,
+    ind_r: usize,
+    len: usize,
+    policy: OverflowPolicy,
+}
+
+impl<T> CircularBuffer<T> {
+    // una capacità nulla viene portata a 1
+    pub fn new(max: usize, policy: OverflowPolicy) -> Self {
+        let max = max.max(1);
+        CircularBuffer {
+            tape: (0..max).map(|_| None).collect(),
+            ind_r: 0,
+            len: 0,
+            policy,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.tape.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    // indice nel nastro della posizione logica `i` (0 = elemento più vecchio)
+    fn slot(&self, i: usize) -> usize {
+        (self.ind_r + i) % self.capacity()
+    }
+
+    // inserisce in coda; con `Overwrite` e buffer pieno restituisce l'elemento
+    // più vecchio che è stato tolto
+    pub fn insert(&mut self, value: T) -> Result<Option<T>, BufferError<T>> {
+        let mut evicted = None;
+        if self.is_full() {
+            match self.policy {
+                OverflowPolicy::Reject => return Err(BufferError::Full(value)),
+                OverflowPolicy::Overwrite => evicted = self.remove(),
+            }
+        }
+
+        let ind_w = self.slot(self.len);
+        self.tape[ind_w] = Some(value);
+        self.len += 1;
+        Ok(evicted)
+    }
+
+    // toglie l'elemento più vecchio
+    pub fn remove(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let value = self.tape[self.ind_r].take();
+        self.ind_r = self.slot(1);
+        self.len -= 1;
+        value
+    }
+
+    pub fn clear(&mut self) {
+        while self.remove().is_some() {}
+        self.ind_r = 0;
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i < self.len {
+            self.tape[self.slot(i)].as_ref()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i < self.len {
+            let slot = self.slot(i);
+            self.tape[slot].as_mut()
+        } else {
+            None
+        }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|i| self.get(i))
+    }
+
+    // dal più vecchio al più recente
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            buffer: self,
+            front: 0,
+            back: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        // le due metà del nastro, nell'ordine logico
+        let (head, tail) = self.tape.split_at_mut(self.ind_r);
+        let len = self.len;
+        IterMut {
+            inner: tail.iter_mut().chain(head.iter_mut()).take(len),
+        }
+    }
+}
+
+impl<T: Debug> Debug for CircularBuffer<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Clone> Clone for CircularBuffer<T> {
+    fn clone(&self) -> Self {
+        CircularBuffer {
+            tape: self.tape.clone(),
+            ind_r: self.ind_r,
+            len: self.len,
+            policy: self.policy,
+        }
+    }
+}
+
+impl<T> Index<usize> for CircularBuffer<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        let len = self.len;
+        self.get(i)
+            .unwrap_or_else(|| panic!("index {} out of range for buffer of length {}", i, len))
+    }
+}
+
+impl<T> IndexMut<usize> for CircularBuffer<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let len = self.len;
+        self.get_mut(i)
+            .unwrap_or_else(|| panic!("index {} out of range for buffer of length {}", i, len))
+    }
+}
+
+pub struct Iter<'a, T> {
+    buffer: &'a CircularBuffer<T>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        self.buffer.get(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.buffer.get(self.back)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
+
+type Slots<'a, T> = std::iter::Take<
+    std::iter::Chain<std::slice::IterMut<'a, Option<T>>, std::slice::IterMut<'a, Option<T>>>,
+>;
+
+pub struct IterMut<'a, T> {
+    inner: Slots<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.inner.next().and_then(Option::as_mut)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+// consuma il buffer togliendo gli elementi dal più vecchio
+pub struct IntoIter<T> {
+    buffer: CircularBuffer<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.buffer.remove()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.buffer.len, Some(self.buffer.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for CircularBuffer<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { buffer: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a CircularBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut CircularBuffer<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BufferError, CircularBuffer, OverflowPolicy};
+
+    #[test]
+    fn reject_when_full() {
+        let mut buffer = CircularBuffer::new(2, OverflowPolicy::Reject);
+        assert_eq!(buffer.insert("a"), Ok(None));
+        assert_eq!(buffer.insert("b"), Ok(None));
+        assert_eq!(buffer.insert("c"), Err(BufferError::Full("c")));
+        assert_eq!(buffer.remove(), Some("a"));
+        assert_eq!(buffer.insert("c"), Ok(None));
+        assert_eq!(buffer.iter().collect::<Vec<_>>(), [&"b", &"c"]);
+    }
+
+    #[test]
+    fn overwrite_oldest() {
+        let mut buffer = CircularBuffer::new(3, OverflowPolicy::Overwrite);
+        for i in 0..5 {
+            buffer.insert(i).unwrap();
+        }
+        assert_eq!(buffer.insert(5), Ok(Some(2)));
+        assert_eq!(format!("{:?}", buffer), "[3, 4, 5]");
+        assert_eq!((buffer[0], buffer[2]), (3, 5));
+        assert_eq!(buffer.iter().rev().copied().collect::<Vec<_>>(), [5, 4, 3]);
+
+        for value in &mut buffer {
+            *value *= 10;
+        }
+        buffer[1] += 1;
+        assert_eq!(buffer.into_iter().collect::<Vec<_>>(), [30, 41, 50]);
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut buffer = CircularBuffer::new(0, OverflowPolicy::Reject);
+        assert_eq!(buffer.capacity(), 1);
+        assert_eq!(buffer.insert(String::from("x")), Ok(None));
+        assert!(buffer.is_full());
+    }
+
+    #[test]
+    #[should_panic(expected = "index 1 out of range")]
+    fn index_out_of_range() {
+        let mut buffer = CircularBuffer::new(4, OverflowPolicy::Reject);
+        buffer.insert(1).unwrap();
+        let _ = buffer[1];
+    }
+}
@@ -0,0 +1,92 @@
+use esercizio_1::{BufferError, CircularBuffer, OverflowPolicy};
+use proptest::prelude::*;
+use std::collections::VecDeque;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Insert(u8),
+    Remove,
+    Clear,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        6 => any::<u8>().prop_map(Op::Insert),
+        3 => Just(Op::Remove),
+        1 => Just(Op::Clear),
+    ]
+}
+
+fn policy() -> impl Strategy<Value = OverflowPolicy> {
+    prop_oneof![
+        Just(OverflowPolicy::Reject),
+        Just(OverflowPolicy::Overwrite)
+    ]
+}
+
+proptest! {
+    // dopo ogni operazione il buffer deve comportarsi come una VecDeque limitata
+    #[test]
+    fn behaves_like_a_bounded_deque(
+        capacity in 1usize..8,
+        policy in policy(),
+        ops in prop::collection::vec(op(), 0..200),
+    ) {
+        let mut buffer = CircularBuffer::new(capacity, policy);
+        let mut model = VecDeque::new();
+
+        for op in ops {
+            match op {
+                Op::Insert(value) => {
+                    let result = buffer.insert(value);
+                    if model.len() < capacity {
+                        model.push_back(value);
+                        prop_assert_eq!(result, Ok(None));
+                    } else if policy == OverflowPolicy::Reject {
+                        prop_assert_eq!(result, Err(BufferError::Full(value)));
+                    } else {
+                        let oldest = model.pop_front();
+                        model.push_back(value);
+                        prop_assert_eq!(result, Ok(oldest));
+                    }
+                }
+                Op::Remove => prop_assert_eq!(buffer.remove(), model.pop_front()),
+                Op::Clear => {
+                    buffer.clear();
+                    model.clear();
+                }
+            }
+
+            prop_assert_eq!(buffer.len(), model.len());
+            prop_assert_eq!(buffer.is_full(), model.len() == capacity);
+            prop_assert!(buffer.iter().eq(model.iter()));
+            prop_assert!(buffer.iter().rev().eq(model.iter().rev()));
+            prop_assert_eq!(buffer.iter().len(), model.len());
+            for (i, value) in model.iter().enumerate() {
+                prop_assert_eq!(&buffer[i], value);
+            }
+            prop_assert_eq!(buffer.get(model.len()), None);
+            prop_assert_eq!(buffer.front(), model.front());
+            prop_assert_eq!(buffer.back(), model.back());
+        }
+
+        prop_assert!(buffer.into_iter().eq(model.into_iter()));
+    }
+
+    #[test]
+    fn iter_mut_visits_in_order(
+        capacity in 1usize..8,
+        values in prop::collection::vec(any::<u16>(), 0..20),
+    ) {
+        let mut buffer = CircularBuffer::new(capacity, OverflowPolicy::Overwrite);
+        for &value in &values {
+            buffer.insert(value).unwrap();
+        }
+        for (i, value) in buffer.iter_mut().enumerate() {
+            *value = i as u16;
+        }
+
+        let expected: Vec<u16> = (0..values.len().min(capacity) as u16).collect();
+        prop_assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), expected);
+    }
+}
@@ -0,0 +1,222 @@
+use std::iter::FusedIterator;
+
+// ripete l'iteratore `repeat` volte in tutto; come Flatten tiene un iteratore
+// per il fronte e uno per il fondo, con in mezzo i giri non ancora iniziati
+#[derive(Clone, Debug)]
+pub struct MyCycle<I> {
+    orig: I,
+    front: Option<I>,
+    back: Option<I>,
+    middle: usize,
+}
+
+impl<I: Clone + Iterator> MyCycle<I> {
+    pub fn new(iter: I, repeat: usize) -> Self {
+        MyCycle {
+            front: (repeat > 0).then(|| iter.clone()),
+            back: None,
+            middle: repeat.saturating_sub(1),
+            orig: iter,
+        }
+    }
+
+    fn next_round(&mut self) -> Option<I> {
+        if self.middle == 0 {
+            return None;
+        }
+        self.middle -= 1;
+        Some(self.orig.clone())
+    }
+}
+
+impl<I: Clone + Iterator> Iterator for MyCycle<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(x) = self.front.as_mut().and_then(Iterator::next) {
+            return Some(x);
+        }
+        if let Some(mut round) = self.next_round() {
+            if let Some(x) = round.next() {
+                self.front = Some(round);
+                return Some(x);
+            }
+            // se un giro nuovo è vuoto lo sono anche tutti gli altri
+            self.middle = 0;
+        }
+        self.front = None;
+        self.back.as_mut()?.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let part = |iter: &Option<I>| iter.as_ref().map_or((0, Some(0)), Iterator::size_hint);
+        let (front_lo, front_hi) = part(&self.front);
+        let (back_lo, back_hi) = part(&self.back);
+        let (orig_lo, orig_hi) = if self.middle > 0 {
+            self.orig.size_hint()
+        } else {
+            (0, Some(0))
+        };
+
+        let lo = orig_lo
+            .saturating_mul(self.middle)
+            .saturating_add(front_lo)
+            .saturating_add(back_lo);
+        let hi = match (front_hi, back_hi, orig_hi) {
+            (Some(f), Some(b), Some(o)) => o
+                .checked_mul(self.middle)
+                .and_then(|m| m.checked_add(f))
+                .and_then(|m| m.checked_add(b)),
+            _ => None,
+        };
+        (lo, hi)
+    }
+}
+
+impl<I: Clone + DoubleEndedIterator> DoubleEndedIterator for MyCycle<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if let Some(x) = self.back.as_mut().and_then(DoubleEndedIterator::next_back) {
+            return Some(x);
+        }
+        if let Some(mut round) = self.next_round() {
+            if let Some(x) = round.next_back() {
+                self.back = Some(round);
+                return Some(x);
+            }
+            self.middle = 0;
+        }
+        self.back = None;
+        self.front.as_mut()?.next_back()
+    }
+}
+
+// size_hint è esatto finché la lunghezza totale sta in un usize; oltre, come per gli
+// adattatori di std che possono andare in overflow, len() va in panic
+impl<I: Clone + ExactSizeIterator> ExactSizeIterator for MyCycle<I> {}
+// come per Flatten, finito il fronte si continua a chiamare il fondo, quindi
+// l'iteratore è fused solo se lo è quello ripetuto
+impl<I: Clone + FusedIterator> FusedIterator for MyCycle<I> {}
+
+// ripete l'iteratore all'infinito, ma termina subito se è vuoto
+#[derive(Clone, Debug)]
+pub struct MyCycleForever<I> {
+    orig: I,
+    iter: I,
+}
+
+impl<I: Clone + Iterator> MyCycleForever<I> {
+    pub fn new(iter: I) -> Self {
+        MyCycleForever {
+            iter: iter.clone(),
+            orig: iter,
+        }
+    }
+}
+
+impl<I: Clone + Iterator> Iterator for MyCycleForever<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.iter.next() {
+            Some(x) => Some(x),
+            None => {
+                self.iter = self.orig.clone();
+                self.iter.next()
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.orig.size_hint() {
+            (_, Some(0)) => self.iter.size_hint(),
+            (0, _) => (self.iter.size_hint().0, None),
+            _ => (usize::MAX, None),
+        }
+    }
+}
+
+pub trait MyCycleExt: Iterator + Clone + Sized {
+    fn my_cycle(self, repeat: usize) -> MyCycle<Self> {
+        MyCycle::new(self, repeat)
+    }
+
+    fn my_cycle_forever(self) -> MyCycleForever<Self> {
+        MyCycleForever::new(self)
+    }
+}
+
+impl<I: Iterator + Clone> MyCycleExt for I {}
+
+#[cfg(test)]
+mod test {
+    use super::MyCycleExt;
+
+    #[test]
+    fn repeats_n_times() {
+        let v = [0, 1, 2];
+        assert_eq!(
+            v.iter().my_cycle(3).copied().collect::<Vec<_>>(),
+            [0, 1, 2, 0, 1, 2, 0, 1, 2]
+        );
+        assert_eq!(v.iter().my_cycle(1).count(), 3);
+        assert_eq!(v.iter().my_cycle(0).next(), None);
+        assert_eq!([0; 0].iter().my_cycle(usize::MAX).next(), None);
+        assert_eq!((0..9).filter(|_| false).my_cycle(usize::MAX).next(), None);
+    }
+
+    #[test]
+    fn size_hint_and_len() {
+        let mut cycle = [0, 1, 2].iter().my_cycle(2);
+        assert_eq!(cycle.size_hint(), (6, Some(6)));
+        assert_eq!(cycle.len(), 6);
+        cycle.next();
+        assert_eq!(cycle.len(), 5);
+        cycle.next_back();
+        assert_eq!(cycle.len(), 4);
+        assert_eq!(cycle.size_hint(), (4, Some(4)));
+        // la lunghezza non sta in un usize
+        assert_eq!((0..usize::MAX).my_cycle(2).size_hint(), (usize::MAX, None));
+        assert_eq!([1, 2].iter().my_cycle(0).size_hint(), (0, Some(0)));
+        assert_eq!(
+            [1, 2].iter().my_cycle(usize::MAX).size_hint(),
+            (usize::MAX, None)
+        );
+        // un iteratore senza limite superiore noto
+        let filtered = (0..4).filter(|x| x % 2 == 0).my_cycle(2);
+        assert_eq!(filtered.size_hint(), (0, Some(8)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn len_overflow_panics() {
+        let _ = (0..usize::MAX).my_cycle(2).len();
+    }
+
+    #[test]
+    fn double_ended() {
+        let cycle = (0..3).my_cycle(2);
+        assert_eq!(cycle.clone().rev().collect::<Vec<_>>(), [2, 1, 0, 2, 1, 0]);
+
+        // fronte e fondo si incontrano nello stesso giro
+        let mut cycle = (0..3).my_cycle(2);
+        assert_eq!(cycle.next(), Some(0));
+        assert_eq!(cycle.next_back(), Some(2));
+        assert_eq!(cycle.next_back(), Some(1));
+        assert_eq!(cycle.next_back(), Some(0));
+        assert_eq!(cycle.next(), Some(1));
+        assert_eq!(cycle.next(), Some(2));
+        assert_eq!(cycle.next(), None);
+        assert_eq!(cycle.next_back(), None);
+    }
+
+    #[test]
+    fn forever() {
+        assert_eq!(
+            "ab".chars().my_cycle_forever().take(5).collect::<String>(),
+            "ababa"
+        );
+        assert_eq!("".chars().my_cycle_forever().next(), None);
+        assert_eq!((0..2).my_cycle_forever().size_hint(), (usize::MAX, None));
+        assert_eq!((0..0).my_cycle_forever().size_hint(), (0, Some(0)));
+    }
+}
@@ -1,44 +1,9 @@
-
-#[derive(Debug)]
-struct MyCycle<I: Clone + Iterator> {
-    val: I,
-    val_clone: I,
-    repeat: usize
-}
-
-impl <I: Clone + Iterator> MyCycle<I> {
-    fn new(iter: I, repeat_val: usize) -> Self {
-        MyCycle {
-            val_clone: iter.clone(),
-            val: iter,
-            repeat: repeat_val
-        }
-    }
-}
-
-impl <I: Clone + Iterator> Iterator for MyCycle<I> {
-    type Item = I::Item;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.val.next() {
-            Some(x) => Some(x),
-            None => {
-                self.repeat -= 1;
-                if self.repeat <= 0 {
-                    None
-                } else {
-                    self.val = self.val_clone.clone();
-                    self.val.next()
-                }
-            }
-        }
-    }
-}
+use esercizio_2::MyCycleExt;
 
 fn main() {
-    let iter = vec![0, 1, 2, 3];
+    let iter = [0, 1, 2, 3];
     let mut repeat = 3;
-    let mut my_cycle = MyCycle::new(iter.iter(), repeat.clone());
+    let mut my_cycle = iter.iter().my_cycle(repeat);
 
     while repeat != 0 {
         for i in 0..iter.len() {
@@ -46,5 +11,10 @@ fn main() {
         }
         repeat -= 1;
     }
+    assert_eq!(my_cycle.next(), None);
 
+    let rev: Vec<_> = iter.iter().my_cycle(2).rev().collect();
+    println!("{:?}", rev);
+    let forever: Vec<_> = iter.iter().my_cycle_forever().take(10).collect();
+    println!("{:?}", forever);
 }
@@ -0,0 +1,538 @@
+use crate::{common_free_slots, Calendar, Date, DateError, DateTime, Interval, TimeOfDay, Weekday};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    // giorni ordinati e senza ripetizioni, vuoto per il giorno d'inizio dell'evento
+    Weekly(Vec<Weekday>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecurrenceEnd {
+    Never,
+    // ultima data (compresa) in cui può iniziare una ripetizione
+    Until(Date),
+    // numero di ripetizioni, contando anche quelle saltate dalle eccezioni
+    Count(u32),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recurrence {
+    frequency: Frequency,
+    every: u32,
+    end: RecurrenceEnd,
+}
+
+impl Recurrence {
+    // più dei giorni tra il primo e l'ultimo anno rappresentabile: un passo più
+    // lungo non cambia niente e terrebbe i conti sui giorni lontani dall'overflow
+    pub const MAX_EVERY: u32 = 3_660_000;
+
+    pub fn daily() -> Self {
+        Recurrence {
+            frequency: Frequency::Daily,
+            every: 1,
+            end: RecurrenceEnd::Never,
+        }
+    }
+
+    pub fn weekly(days: impl IntoIterator<Item = Weekday>) -> Self {
+        let mut days: Vec<Weekday> = days.into_iter().collect();
+        days.sort();
+        days.dedup();
+        Recurrence {
+            frequency: Frequency::Weekly(days),
+            ..Recurrence::daily()
+        }
+    }
+
+    // ogni `every` giorni o settimane, tra 1 e MAX_EVERY
+    pub fn every(mut self, every: u32) -> Self {
+        self.every = every.clamp(1, Recurrence::MAX_EVERY);
+        self
+    }
+
+    pub fn until(mut self, date: Date) -> Self {
+        self.end = RecurrenceEnd::Until(date);
+        self
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.end = RecurrenceEnd::Count(count);
+        self
+    }
+
+    pub fn frequency(&self) -> &Frequency {
+        &self.frequency
+    }
+
+    pub fn end(&self) -> RecurrenceEnd {
+        self.end
+    }
+
+    // date d'inizio delle ripetizioni di un evento che inizia in `start`, in ordine,
+    // dalla prima che non viene prima di `from`: quelle precedenti si saltano con un
+    // calcolo, ma contano comunque per il numero di ripetizioni
+    fn dates(&self, start: Date, from: Date) -> Box<dyn Iterator<Item = Date> + '_> {
+        let every = i64::from(self.every);
+        let skip = i64::from(start.days_until(from).max(0));
+        // (numero della ripetizione, data)
+        let dates: Box<dyn Iterator<Item = (i64, Date)>> = match &self.frequency {
+            Frequency::Daily => {
+                let first = (skip + every - 1) / every;
+                Box::new((first..).map(move |k| (k, start.add_days((k * every) as i32))))
+            }
+            Frequency::Weekly(days) => {
+                let days = if days.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    days.clone()
+                };
+                let per_week = days.len() as i64;
+                // le settimane iniziano di lunedì, quella dell'evento è la prima; i suoi
+                // giorni che precedono l'inizio non sono ripetizioni
+                let monday = start.week_start();
+                let before_start = days.iter().filter(|day| **day < start.weekday()).count();
+                let week = i64::from(monday.days_until(from).max(0)) / (7 * every);
+                Box::new(
+                    (week * per_week..)
+                        .map(move |i| {
+                            let offset =
+                                i / per_week * 7 * every + days[(i % per_week) as usize] as i64;
+                            (i - before_start as i64, monday.add_days(offset as i32))
+                        })
+                        .filter(move |(_, date)| *date >= start),
+                )
+            }
+        };
+
+        let dates: Box<dyn Iterator<Item = (i64, Date)>> = match self.end {
+            RecurrenceEnd::Never => dates,
+            RecurrenceEnd::Until(until) => {
+                Box::new(dates.take_while(move |(_, date)| *date <= until))
+            }
+            RecurrenceEnd::Count(count) => {
+                Box::new(dates.take_while(move |(k, _)| *k < i64::from(count)))
+            }
+        };
+        Box::new(
+            dates
+                .map(|(_, date)| date)
+                .skip_while(move |date| *date < from),
+        )
+    }
+}
+
+// impegno che può durare più giorni e ripetersi
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    start: DateTime,
+    end: DateTime,
+    recurrence: Option<Recurrence>,
+    // date d'inizio delle ripetizioni saltate, ordinate
+    exceptions: Vec<Date>,
+}
+
+impl Event {
+    pub fn new(start: DateTime, end: DateTime) -> Result<Self, DateError> {
+        if start >= end {
+            return Err(DateError::EmptyEvent(start, end));
+        }
+        Ok(Event {
+            start,
+            end,
+            recurrence: None,
+            exceptions: Vec::new(),
+        })
+    }
+
+    // evento dentro una sola giornata
+    pub fn on(date: Date, busy: Interval) -> Self {
+        Event {
+            start: DateTime::new(date, busy.start()),
+            end: DateTime::new(date, busy.end()),
+            recurrence: None,
+            exceptions: Vec::new(),
+        }
+    }
+
+    pub fn repeat(mut self, recurrence: Recurrence) -> Self {
+        self.recurrence = Some(recurrence);
+        self
+    }
+
+    // salta la ripetizione che inizia in quella data
+    pub fn except(mut self, date: Date) -> Self {
+        if let Err(pos) = self.exceptions.binary_search(&date) {
+            self.exceptions.insert(pos, date);
+        }
+        self
+    }
+
+    pub fn start(&self) -> DateTime {
+        self.start
+    }
+
+    pub fn end(&self) -> DateTime {
+        self.end
+    }
+
+    pub fn recurrence(&self) -> Option<&Recurrence> {
+        self.recurrence.as_ref()
+    }
+
+    // ripetizioni (inizio, fine) che iniziano tra `from` e `to` compresi
+    pub fn occurrences(
+        &self,
+        from: Date,
+        to: Date,
+    ) -> impl Iterator<Item = (DateTime, DateTime)> + '_ {
+        let first = self.start.date();
+        let span = first.days_until(self.end.date());
+        let dates = match &self.recurrence {
+            Some(recurrence) => recurrence.dates(first, from),
+            None => Box::new(std::iter::once(first)),
+        };
+        dates
+            .take_while(move |date| *date <= to)
+            .filter(move |date| *date >= from && self.exceptions.binary_search(date).is_err())
+            .map(move |date| {
+                (
+                    DateTime::new(date, self.start.time()),
+                    DateTime::new(date.add_days(span), self.end.time()),
+                )
+            })
+    }
+
+    // parti delle ripetizioni che cadono nella data, come intervalli della giornata
+    pub fn busy_on(&self, date: Date) -> Vec<Interval> {
+        let span = self.start.date().days_until(self.end.date());
+        self.occurrences(date.add_days(-span), date)
+            .filter_map(|(start, end)| {
+                let start = if start.date() == date {
+                    start.time()
+                } else {
+                    TimeOfDay::MIDNIGHT
+                };
+                let end = if end.date() == date {
+                    end.time()
+                } else {
+                    TimeOfDay::END_OF_DAY
+                };
+                Interval::new(start, end).ok()
+            })
+            .collect()
+    }
+}
+
+// impegni di più giorni con gli stessi limiti per ogni giornata
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Agenda {
+    hours: Interval,
+    events: Vec<Event>,
+}
+
+impl Agenda {
+    pub fn new(hours: Interval) -> Self {
+        Agenda {
+            hours,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn hours(&self) -> Interval {
+        self.hours
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn add(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    // calendario di una sola giornata
+    pub fn day(&self, date: Date) -> Calendar {
+        let mut calendar = Calendar::new(self.hours);
+        for busy in self.events.iter().flat_map(|event| event.busy_on(date)) {
+            calendar.insert(busy);
+        }
+        calendar
+    }
+}
+
+// intervalli liberi per tutte le agende lunghi almeno `duration` minuti, dal giorno
+// `from` al giorno `to` compresi, in ordine; uno slot non prosegue nel giorno dopo
+pub fn common_free_slots_between(
+    agendas: &[Agenda],
+    from: Date,
+    to: Date,
+    duration: u16,
+) -> Vec<(Date, Interval)> {
+    free_slots_between(agendas, from, to, duration).collect()
+}
+
+// il primo slot di `duration` minuti libero per tutte le agende, per esempio la
+// prima mezz'ora della settimana: first_common_slot(&agende, lunedì, domenica, 30)
+pub fn first_common_slot(
+    agendas: &[Agenda],
+    from: Date,
+    to: Date,
+    duration: u16,
+) -> Option<(Date, Interval)> {
+    let (date, free) = free_slots_between(agendas, from, to, duration).next()?;
+    let end = free.start().add_minutes(duration.max(1))?;
+    Some((date, Interval::new(free.start(), end).ok()?))
+}
+
+fn free_slots_between(
+    agendas: &[Agenda],
+    from: Date,
+    to: Date,
+    duration: u16,
+) -> impl Iterator<Item = (Date, Interval)> + '_ {
+    (0..=from.days_until(to)).flat_map(move |k| {
+        let date = from.add_days(k);
+        let days: Vec<Calendar> = agendas.iter().map(|agenda| agenda.day(date)).collect();
+        common_free_slots(&days, duration)
+            .into_iter()
+            .map(move |slot| (date, slot))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        common_free_slots_between, first_common_slot, Agenda, Event, Recurrence, RecurrenceEnd,
+    };
+    use crate::{Date, DateError, DateTime, Interval, Weekday};
+
+    fn date(s: &str) -> Date {
+        s.parse().unwrap()
+    }
+
+    fn interval(start: &str, end: &str) -> Interval {
+        Interval::new(start.parse().unwrap(), end.parse().unwrap()).unwrap()
+    }
+
+    fn at(day: &str, time: &str) -> DateTime {
+        DateTime::new(date(day), time.parse().unwrap())
+    }
+
+    fn days_busy(event: &Event, from: &str, days: i32) -> Vec<String> {
+        (0..days)
+            .map(|k| date(from).add_days(k))
+            .filter(|day| !event.busy_on(*day).is_empty())
+            .map(|day| day.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn daily_with_count_and_exceptions() {
+        let event = Event::on(date("2024-01-01"), interval("09:00", "09:30"))
+            .repeat(Recurrence::daily().count(5))
+            .except(date("2024-01-03"));
+        assert_eq!(
+            days_busy(&event, "2023-12-30", 10),
+            ["2024-01-01", "2024-01-02", "2024-01-04", "2024-01-05"]
+        );
+        assert_eq!(
+            event.busy_on(date("2024-01-02")),
+            [interval("09:00", "09:30")]
+        );
+
+        let every_third = Event::on(date("2024-01-01"), interval("09:00", "09:30"))
+            .repeat(Recurrence::daily().every(3).until(date("2024-01-10")));
+        assert_eq!(
+            days_busy(&every_third, "2024-01-01", 20),
+            ["2024-01-01", "2024-01-04", "2024-01-07", "2024-01-10"]
+        );
+    }
+
+    #[test]
+    fn weekly_on_given_days() {
+        let recurrence = Recurrence::weekly([Weekday::Wednesday, Weekday::Monday, Weekday::Monday])
+            .every(2)
+            .until(date("2024-01-31"));
+        assert_eq!(recurrence.end(), RecurrenceEnd::Until(date("2024-01-31")));
+        let event = Event::on(date("2024-01-03"), interval("14:00", "15:00")).repeat(recurrence);
+        let starts: Vec<String> = event
+            .occurrences(date("2023-01-01"), date("2024-12-31"))
+            .map(|(start, _)| start.to_string())
+            .collect();
+        // il lunedì della prima settimana viene prima dell'inizio dell'evento
+        assert_eq!(
+            starts,
+            [
+                "2024-01-03 14:00",
+                "2024-01-15 14:00",
+                "2024-01-17 14:00",
+                "2024-01-29 14:00",
+                "2024-01-31 14:00"
+            ]
+        );
+
+        // senza giorni indicati si ripete nel giorno in cui inizia
+        let friday = Event::on(date("2024-01-05"), interval("17:00", "18:00"))
+            .repeat(Recurrence::weekly([]).count(3));
+        assert_eq!(
+            days_busy(&friday, "2024-01-01", 30),
+            ["2024-01-05", "2024-01-12", "2024-01-19"]
+        );
+    }
+
+    #[test]
+    fn skip_to_the_first_date_asked() {
+        let starts = |event: &Event, from: &str| -> Vec<String> {
+            event
+                .occurrences(date(from), date("9999-12-31"))
+                .map(|(start, _)| start.date().to_string())
+                .collect()
+        };
+
+        // le ripetizioni saltate contano per il limite
+        let every_third = Event::on(date("2024-01-01"), interval("09:00", "09:30"))
+            .repeat(Recurrence::daily().every(3).count(4));
+        assert_eq!(
+            starts(&every_third, "2024-01-05"),
+            ["2024-01-07", "2024-01-10"]
+        );
+        assert!(starts(&every_third, "2024-01-11").is_empty());
+
+        let weekly = Event::on(date("2024-01-03"), interval("14:00", "15:00")).repeat(
+            Recurrence::weekly([Weekday::Monday, Weekday::Wednesday, Weekday::Friday])
+                .every(2)
+                .count(5),
+        );
+        assert_eq!(
+            starts(&weekly, "2023-01-01"),
+            [
+                "2024-01-03",
+                "2024-01-05",
+                "2024-01-15",
+                "2024-01-17",
+                "2024-01-19"
+            ]
+        );
+        assert_eq!(starts(&weekly, "2024-01-16"), ["2024-01-17", "2024-01-19"]);
+        assert!(starts(&weekly, "2024-01-20").is_empty());
+
+        // dall'anno 1 all'anno 9999 senza ripercorrere le date intermedie
+        let fridays = Event::on(date("0001-01-01"), interval("09:00", "09:30"))
+            .repeat(Recurrence::weekly([Weekday::Friday]));
+        for day in (0..7).map(|k| date("9999-12-25").add_days(k)) {
+            let busy = !fridays.busy_on(day).is_empty();
+            assert_eq!(busy, day.weekday() == Weekday::Friday, "{}", day);
+        }
+        let daily =
+            Event::on(date("0001-01-01"), interval("09:00", "09:30")).repeat(Recurrence::daily());
+        assert_eq!(
+            daily.busy_on(date("9999-12-31")),
+            [interval("09:00", "09:30")]
+        );
+
+        // un passo enorme non diventa negativo
+        let huge = Event::on(date("2024-01-01"), interval("09:00", "09:30"))
+            .repeat(Recurrence::daily().every(u32::MAX));
+        assert_eq!(starts(&huge, "0001-01-01"), ["2024-01-01"]);
+        let huge = huge.repeat(Recurrence::weekly([Weekday::Monday]).every(u32::MAX));
+        assert_eq!(starts(&huge, "0001-01-01"), ["2024-01-01"]);
+        assert!(starts(&huge, "2024-01-02").is_empty());
+    }
+
+    #[test]
+    fn multi_day_events() {
+        let trip = Event::new(at("2024-01-05", "22:00"), at("2024-01-07", "02:00")).unwrap();
+        assert_eq!(
+            trip.busy_on(date("2024-01-05")),
+            [interval("22:00", "24:00")]
+        );
+        assert_eq!(trip.busy_on(date("2024-01-06")), [Interval::WHOLE_DAY]);
+        assert_eq!(
+            trip.busy_on(date("2024-01-07")),
+            [interval("00:00", "02:00")]
+        );
+        assert!(trip.busy_on(date("2024-01-08")).is_empty());
+
+        // un turno di notte ogni giorno, saltato il 2 gennaio
+        let night = Event::new(at("2024-01-01", "22:00"), at("2024-01-02", "06:00"))
+            .unwrap()
+            .repeat(Recurrence::daily())
+            .except(date("2024-01-02"));
+        assert_eq!(
+            night.busy_on(date("2024-01-02")),
+            [interval("00:00", "06:00")]
+        );
+        assert_eq!(
+            night.busy_on(date("2024-01-03")),
+            [interval("22:00", "24:00")]
+        );
+        assert_eq!(night.busy_on(date("2024-01-04")).len(), 2);
+
+        // finire a mezzanotte non occupa il giorno dopo
+        let evening = Event::on(date("2024-01-05"), interval("20:00", "24:00"));
+        assert!(evening.busy_on(date("2024-01-06")).is_empty());
+        assert_eq!(
+            Event::new(at("2024-01-05", "10:00"), at("2024-01-05", "10:00")),
+            Err(DateError::EmptyEvent(
+                at("2024-01-05", "10:00"),
+                at("2024-01-05", "10:00")
+            ))
+        );
+    }
+
+    #[test]
+    fn first_slot_of_the_week() {
+        let monday = date("2024-01-01");
+        let sunday = monday.add_days(6);
+        let working_days = [
+            Weekday::Monday,
+            Weekday::Tuesday,
+            Weekday::Wednesday,
+            Weekday::Thursday,
+            Weekday::Friday,
+        ];
+
+        let mut alice = Agenda::new(interval("09:00", "18:00"));
+        alice.add(
+            Event::on(date("2023-12-01"), interval("09:00", "09:30")).repeat(Recurrence::daily()),
+        );
+        alice.add(Event::on(monday, Interval::WHOLE_DAY));
+
+        let mut bob = Agenda::new(interval("08:00", "17:00"));
+        bob.add(
+            Event::on(monday, interval("09:30", "12:00")).repeat(Recurrence::weekly(working_days)),
+        );
+        bob.add(
+            Event::on(monday, interval("13:00", "17:00"))
+                .repeat(Recurrence::weekly(working_days))
+                .except(date("2024-01-03")),
+        );
+        let agendas = [alice, bob];
+
+        assert_eq!(
+            first_common_slot(&agendas, monday, sunday, 30),
+            Some((date("2024-01-02"), interval("12:00", "12:30")))
+        );
+        assert_eq!(
+            first_common_slot(&agendas, monday, sunday, 90),
+            Some((date("2024-01-03"), interval("12:00", "13:30")))
+        );
+        assert_eq!(first_common_slot(&agendas, monday, monday, 30), None);
+
+        let free: Vec<String> = common_free_slots_between(&agendas, monday, sunday, 90)
+            .iter()
+            .map(|(date, slot)| format!("{} {}", date, slot))
+            .collect();
+        assert_eq!(
+            free,
+            [
+                "2024-01-03 12:00-17:00",
+                "2024-01-06 09:30-17:00",
+                "2024-01-07 09:30-17:00"
+            ]
+        );
+        assert!(common_free_slots_between(&agendas, sunday, monday, 1).is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+use crate::TimeOfDay;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// giorno del calendario gregoriano, contato in giorni dal 1970-01-01
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(i32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DateError {
+    InvalidFormat(String),
+    OutOfRange(String),
+    // l'evento non finisce dopo il suo inizio
+    EmptyEvent(DateTime, DateTime),
+}
+
+impl Display for DateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateError::InvalidFormat(s) => write!(f, "invalid date {:?}, expected YYYY-MM-DD", s),
+            DateError::OutOfRange(s) => write!(f, "date {} does not exist", s),
+            DateError::EmptyEvent(start, end) => {
+                write!(f, "event {} - {} does not end after it starts", start, end)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DateError {}
+
+fn is_leap(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl Date {
+    // anni da 1 a 9999
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=9999).contains(&year) || !(1..=12).contains(&month) {
+            return None;
+        }
+        if day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+
+        // i mesi partono da marzo, così il giorno bisestile è l'ultimo dell'anno
+        let y = if month <= 2 { year - 1 } else { year };
+        let era = y.div_euclid(400);
+        let year_of_era = y - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let day_of_era =
+            year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year as i32;
+        Some(Date(era * 146097 + day_of_era - 719468))
+    }
+
+    // (anno, mese, giorno)
+    pub fn ymd(self) -> (i32, u32, u32) {
+        let days = self.0 + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = year_of_era + era * 400 + i32::from(month <= 2);
+        (year, month, day)
+    }
+
+    pub fn weekday(self) -> Weekday {
+        // il 1970-01-01 era un giovedì
+        Weekday::ALL[(self.0 + 3).rem_euclid(7) as usize]
+    }
+
+    pub fn add_days(self, days: i32) -> Self {
+        Date(self.0 + days)
+    }
+
+    // giorni da `self` a `other`, negativi se `other` viene prima
+    pub fn days_until(self, other: Date) -> i32 {
+        other.0 - self.0
+    }
+
+    // lunedì della settimana che contiene la data
+    pub fn week_start(self) -> Self {
+        self.add_days(-(self.weekday() as i32))
+    }
+}
+
+impl Weekday {
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DateError::InvalidFormat(s.to_string());
+        let parts: Vec<&str> = s.trim().split('-').collect();
+        let [year, month, day] = parts[..] else {
+            return Err(invalid());
+        };
+        let digits = |x: &str, len: usize| x.len() == len && x.bytes().all(|b| b.is_ascii_digit());
+        if !digits(year, 4) || !digits(month, 2) || !digits(day, 2) {
+            return Err(invalid());
+        }
+
+        let year = year.parse().map_err(|_| invalid())?;
+        let month = month.parse().map_err(|_| invalid())?;
+        let day = day.parse().map_err(|_| invalid())?;
+        Date::new(year, month, day).ok_or_else(|| DateError::OutOfRange(s.to_string()))
+    }
+}
+
+// istante con la precisione del minuto; 24:00 diventa le 00:00 del giorno dopo
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    date: Date,
+    time: TimeOfDay,
+}
+
+impl DateTime {
+    pub fn new(date: Date, time: TimeOfDay) -> Self {
+        if time == TimeOfDay::END_OF_DAY {
+            return DateTime {
+                date: date.add_days(1),
+                time: TimeOfDay::MIDNIGHT,
+            };
+        }
+        DateTime { date, time }
+    }
+
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    pub fn time(&self) -> TimeOfDay {
+        self.time
+    }
+}
+
+impl Display for DateTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.date, self.time)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Date, DateError, DateTime, Weekday};
+    use crate::TimeOfDay;
+
+    #[test]
+    fn civil_dates() {
+        let date = Date::new(2024, 2, 29).unwrap();
+        assert_eq!(date.ymd(), (2024, 2, 29));
+        assert_eq!(date.add_days(1).to_string(), "2024-03-01");
+        assert_eq!(
+            Date::new(1970, 1, 1).unwrap().add_days(-1).to_string(),
+            "1969-12-31"
+        );
+        assert_eq!(Date::new(2023, 2, 29), None);
+        assert_eq!(Date::new(1900, 2, 29), None);
+        assert!(Date::new(2000, 2, 29).is_some());
+
+        // andata e ritorno su tutti i giorni di qualche secolo
+        let first = Date::new(1890, 1, 1).unwrap();
+        for days in 0..100_000 {
+            let date = first.add_days(days);
+            let (y, m, d) = date.ymd();
+            assert_eq!(Date::new(y, m, d), Some(date));
+        }
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!(Date::new(1970, 1, 1).unwrap().weekday(), Weekday::Thursday);
+        let date: Date = "2024-01-05".parse().unwrap();
+        assert_eq!(date.weekday(), Weekday::Friday);
+        assert_eq!(date.week_start().to_string(), "2024-01-01");
+        assert_eq!(date.week_start().days_until(date), 4);
+        let sunday: Date = "2024-01-07".parse().unwrap();
+        assert_eq!(sunday.week_start(), date.week_start());
+    }
+
+    #[test]
+    fn parse_and_datetime() {
+        assert_eq!(
+            "2023-02-29".parse::<Date>(),
+            Err(DateError::OutOfRange("2023-02-29".to_string()))
+        );
+        for bad in ["", "2024-1-05", "24-01-05", "2024/01/05", "2024-01-05-01"] {
+            assert!(bad.parse::<Date>().is_err(), "{}", bad);
+        }
+
+        let date: Date = "2024-12-31".parse().unwrap();
+        let midnight = DateTime::new(date, TimeOfDay::END_OF_DAY);
+        assert_eq!(midnight.to_string(), "2025-01-01 00:00");
+        assert!(DateTime::new(date, "23:59".parse().unwrap()) < midnight);
+    }
+}
@@ -0,0 +1,211 @@
+mod agenda;
+mod date;
+mod parse;
+mod time;
+
+pub use agenda::{
+    common_free_slots_between, first_common_slot, Agenda, Event, Frequency, Recurrence,
+    RecurrenceEnd,
+};
+pub use date::{Date, DateError, DateTime, Weekday};
+pub use parse::{
+    import_ics, parse_calendar, parse_ics, parse_ics_events, read_calendar, ParseError,
+    ParseErrorKind,
+};
+pub use time::{Interval, TimeError, TimeOfDay, MINUTES_PER_DAY};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Calendar {
+    // impegni ordinati per inizio, possono sovrapporsi
+    schedule: Vec<Interval>,
+    bounds: Interval,
+}
+
+impl Calendar {
+    pub fn new(bounds: Interval) -> Self {
+        Self {
+            schedule: Vec::new(),
+            bounds,
+        }
+    }
+
+    pub fn bounds(&self) -> Interval {
+        self.bounds
+    }
+
+    pub fn schedule(&self) -> &[Interval] {
+        &self.schedule
+    }
+
+    // inserisce l'impegno mantenendo l'ordine
+    pub fn insert(&mut self, busy: Interval) {
+        let pos = self.schedule.partition_point(|x| *x <= busy);
+        self.schedule.insert(pos, busy);
+    }
+
+    // intervalli liberi dentro i limiti della giornata lunghi almeno `duration` minuti
+    pub fn free_slots(&self, duration: u16) -> Vec<Interval> {
+        let mut free = Vec::new();
+        let mut cursor = self.bounds.start();
+        for busy in &self.schedule {
+            if busy.start() > cursor {
+                let end = busy.start().min(self.bounds.end());
+                free.extend(Interval::new(cursor, end).ok());
+            }
+            cursor = cursor.max(busy.end());
+            if cursor >= self.bounds.end() {
+                break;
+            }
+        }
+        free.extend(Interval::new(cursor, self.bounds.end()).ok());
+
+        free.retain(|slot| slot.duration() >= duration.max(1));
+        free
+    }
+}
+
+// intersezione di due liste di intervalli ordinati e disgiunti
+pub fn intersect(a: &[Interval], b: &[Interval]) -> Vec<Interval> {
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        result.extend(a[i].intersection(&b[j]));
+        if a[i].end() <= b[j].end() {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    result
+}
+
+// intervalli liberi per tutti i calendari lunghi almeno `duration` minuti, in ordine;
+// ogni calendario conta con i propri limiti, senza calendari non c'è niente di libero
+pub fn common_free_slots<'a>(
+    calendars: impl IntoIterator<Item = &'a Calendar>,
+    duration: u16,
+) -> Vec<Interval> {
+    let mut calendars = calendars.into_iter();
+    let Some(first) = calendars.next() else {
+        return Vec::new();
+    };
+    let mut free = first.free_slots(0);
+    for calendar in calendars {
+        if free.is_empty() {
+            break;
+        }
+        free = intersect(&free, &calendar.free_slots(0));
+    }
+    free.retain(|slot| slot.duration() >= duration.max(1));
+    free
+}
+
+// intervalli liberi per entrambi i calendari lunghi almeno `duration` minuti
+pub fn check_free_range_calendar(
+    calendar_1: &Calendar,
+    calendar_2: &Calendar,
+    duration: u16,
+) -> Vec<Interval> {
+    common_free_slots([calendar_1, calendar_2], duration)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_free_range_calendar, common_free_slots, Calendar, Interval, TimeOfDay};
+
+    fn interval(start: &str, end: &str) -> Interval {
+        Interval::new(start.parse().unwrap(), end.parse().unwrap()).unwrap()
+    }
+
+    fn calendar(bounds: (&str, &str), busy: &[(&str, &str)]) -> Calendar {
+        let mut calendar = Calendar::new(interval(bounds.0, bounds.1));
+        for (start, end) in busy {
+            calendar.insert(interval(start, end));
+        }
+        calendar
+    }
+
+    fn show(slots: &[Interval]) -> Vec<String> {
+        slots.iter().map(|slot| slot.to_string()).collect()
+    }
+
+    #[test]
+    fn free_slots_of_one_calendar() {
+        // impegni fuori ordine, sovrapposti e che escono dai limiti
+        let calendar = calendar(
+            ("08:00", "20:00"),
+            &[
+                ("14:00", "16:00"),
+                ("07:00", "08:20"),
+                ("10:05", "11:35"),
+                ("11:00", "11:40"),
+                ("19:50", "21:00"),
+            ],
+        );
+        assert_eq!(calendar.schedule()[0], interval("07:00", "08:20"));
+        assert_eq!(
+            show(&calendar.free_slots(1)),
+            ["08:20-10:05", "11:40-14:00", "16:00-19:50"]
+        );
+        assert_eq!(show(&calendar.free_slots(150)), ["16:00-19:50"]);
+        assert!(calendar.free_slots(600).is_empty());
+    }
+
+    #[test]
+    fn two_calendars() {
+        let calendar_1 = calendar(
+            ("08:00", "20:00"),
+            &[("10:00", "11:30"), ("14:00", "16:00")],
+        );
+        let calendar_2 = calendar(
+            ("09:00", "18:00"),
+            &[("09:30", "12:00"), ("13:30", "16:30")],
+        );
+        assert_eq!(
+            show(&check_free_range_calendar(&calendar_1, &calendar_2, 30)),
+            ["09:00-09:30", "12:00-13:30", "16:30-18:00"]
+        );
+        assert_eq!(
+            show(&check_free_range_calendar(&calendar_1, &calendar_2, 45)),
+            ["12:00-13:30", "16:30-18:00"]
+        );
+
+        let full_day =
+            Calendar::new(Interval::new(TimeOfDay::MIDNIGHT, TimeOfDay::END_OF_DAY).unwrap());
+        assert_eq!(full_day.free_slots(24 * 60).len(), 1);
+    }
+
+    #[test]
+    fn many_calendars() {
+        let calendars = [
+            calendar(
+                ("08:00", "20:00"),
+                &[("10:00", "11:30"), ("14:00", "16:00")],
+            ),
+            calendar(
+                ("09:00", "18:00"),
+                &[("09:30", "12:00"), ("13:30", "16:30")],
+            ),
+            calendar(
+                ("08:30", "17:00"),
+                &[("12:15", "12:45"), ("16:50", "17:00")],
+            ),
+        ];
+        assert_eq!(
+            show(&common_free_slots(&calendars, 30)),
+            ["09:00-09:30", "12:45-13:30"]
+        );
+        assert_eq!(show(&common_free_slots(&calendars, 40)), ["12:45-13:30"]);
+        // l'ordine dei calendari non cambia il risultato
+        assert_eq!(
+            common_free_slots(calendars.iter().rev(), 1),
+            common_free_slots(&calendars, 1)
+        );
+        assert_eq!(
+            common_free_slots(&calendars[..2], 30),
+            check_free_range_calendar(&calendars[0], &calendars[1], 30)
+        );
+        assert_eq!(common_free_slots(&calendars[..1], 200).len(), 1);
+        assert!(common_free_slots(&[] as &[Calendar], 0).is_empty());
+    }
+}
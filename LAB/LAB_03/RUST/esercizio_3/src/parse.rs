@@ -0,0 +1,655 @@
+use crate::{Calendar, Date, DateError, Interval, TimeError, TimeOfDay, MINUTES_PER_DAY};
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    Io(String),
+    Time(TimeError),
+    Date(DateError),
+    // il file non contiene i limiti della giornata
+    MissingBounds,
+    // orario di inizio senza la fine dell'impegno
+    UnpairedTime(TimeOfDay),
+    // proprietà di un VEVENT con un valore non valido
+    InvalidProperty(String),
+    MissingProperty(&'static str),
+    UnterminatedEvent,
+}
+
+// errore con il file e la riga (da 1) in cui si è verificato, se ce n'è una
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub file: String,
+    pub line: Option<usize>,
+    pub kind: ParseErrorKind,
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseErrorKind::Io(e) => write!(f, "{}", e),
+            ParseErrorKind::Time(e) => write!(f, "{}", e),
+            ParseErrorKind::Date(e) => write!(f, "{}", e),
+            ParseErrorKind::MissingBounds => write!(f, "missing the bounds of the day"),
+            ParseErrorKind::UnpairedTime(t) => {
+                write!(f, "busy interval starting at {} has no end", t)
+            }
+            ParseErrorKind::InvalidProperty(p) => write!(f, "invalid property {:?}", p),
+            ParseErrorKind::MissingProperty(p) => write!(f, "event without {}", p),
+            ParseErrorKind::UnterminatedEvent => write!(f, "BEGIN:VEVENT without END:VEVENT"),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.file, line, self.kind),
+            None => write!(f, "{}: {}", self.file, self.kind),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    fn new(file: &str, line: Option<usize>, kind: ParseErrorKind) -> Self {
+        ParseError {
+            file: file.to_string(),
+            line,
+            kind,
+        }
+    }
+}
+
+// legge un calendario scegliendo il formato dall'estensione (.ics oppure testo)
+pub fn read_calendar(path: impl AsRef<Path>) -> Result<Calendar, ParseError> {
+    let path = path.as_ref();
+    let name = path.display().to_string();
+    let file = File::open(path)
+        .map_err(|e| ParseError::new(&name, None, ParseErrorKind::Io(e.to_string())))?;
+
+    let is_ics = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ics"));
+    if is_ics {
+        parse_ics(&name, BufReader::new(file))
+    } else {
+        parse_calendar(&name, BufReader::new(file))
+    }
+}
+
+// formato testuale: i primi due orari sono i limiti della giornata, i successivi
+// vanno a coppie (inizio e fine di un impegno); su ogni riga uno o due orari,
+// anche nella forma 10:00-11:30; '#' inizia un commento, le righe vuote si saltano
+pub fn parse_calendar(name: &str, input: impl BufRead) -> Result<Calendar, ParseError> {
+    let mut times = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let number = Some(i + 1);
+        let line =
+            line.map_err(|e| ParseError::new(name, number, ParseErrorKind::Io(e.to_string())))?;
+        let content = line.split('#').next().unwrap_or_default().trim();
+
+        let tokens: Vec<&str> = match content.split_once('-') {
+            Some((start, end)) => vec![start, end],
+            None => content.split_whitespace().collect(),
+        };
+        for token in tokens {
+            let time = token
+                .parse()
+                .map_err(|e| ParseError::new(name, number, ParseErrorKind::Time(e)))?;
+            times.push((number, time));
+        }
+    }
+
+    let mut times = times.into_iter();
+    let mut next_interval = || -> Option<Result<Interval, ParseError>> {
+        let (line, start) = times.next()?;
+        let Some((line, end)) = times.next() else {
+            return Some(Err(ParseError::new(
+                name,
+                line,
+                ParseErrorKind::UnpairedTime(start),
+            )));
+        };
+        Some(
+            Interval::new(start, end)
+                .map_err(|e| ParseError::new(name, line, ParseErrorKind::Time(e))),
+        )
+    };
+
+    let bounds = match next_interval() {
+        Some(Err(ParseError {
+            kind: ParseErrorKind::UnpairedTime(_),
+            line,
+            ..
+        })) => return Err(ParseError::new(name, line, ParseErrorKind::MissingBounds)),
+        Some(bounds) => bounds?,
+        None => return Err(ParseError::new(name, None, ParseErrorKind::MissingBounds)),
+    };
+    let mut calendar = Calendar::new(bounds);
+    while let Some(busy) = next_interval() {
+        calendar.insert(busy?);
+    }
+    Ok(calendar)
+}
+
+// calendario dell'intera giornata con gli eventi di un file iCalendar
+pub fn parse_ics(name: &str, input: impl BufRead) -> Result<Calendar, ParseError> {
+    let mut calendar = Calendar::new(Interval::WHOLE_DAY);
+    import_ics(&mut calendar, name, input)?;
+    Ok(calendar)
+}
+
+// aggiunge al calendario i VEVENT del file, restituisce quanti impegni ha inserito;
+// la data degli eventi si scarta e finiscono tutti nella stessa giornata (per tenerla
+// c'è parse_ics_events). Degli orari conta solo l'ora locale indicata, un evento che
+// finisce in un altro giorno occupa la giornata fino a 24:00, quelli trasparenti o
+// cancellati si saltano
+pub fn import_ics(
+    calendar: &mut Calendar,
+    name: &str,
+    input: impl BufRead,
+) -> Result<usize, ParseError> {
+    let mut inserted = 0;
+    read_vevents(name, input, |event| {
+        if let Some(busy) = event.interval(name)? {
+            calendar.insert(busy);
+            inserted += 1;
+        }
+        Ok(())
+    })?;
+    Ok(inserted)
+}
+
+// eventi del file con le loro date, da aggiungere a un'agenda; un evento può durare
+// più giorni, quelli di tutto il giorno senza fine durano un giorno. Come per
+// import_ics conta solo l'ora locale e si saltano gli eventi trasparenti o cancellati
+pub fn parse_ics_events(name: &str, input: impl BufRead) -> Result<Vec<crate::Event>, ParseError> {
+    let mut events = Vec::new();
+    read_vevents(name, input, |event| {
+        events.extend(event.dated(name)?);
+        Ok(())
+    })?;
+    Ok(events)
+}
+
+// chiama `f` su ogni VEVENT del file, nell'ordine; le proprietà dei componenti
+// annidati (per esempio la DURATION di un VALARM) non sono dell'evento
+fn read_vevents(
+    name: &str,
+    input: impl BufRead,
+    mut f: impl FnMut(VEvent) -> Result<(), ParseError>,
+) -> Result<(), ParseError> {
+    let mut event: Option<VEvent> = None;
+    // componenti aperti dentro il VEVENT corrente
+    let mut nested = 0;
+
+    for line in unfold(input) {
+        let (number, line) =
+            line.map_err(|(n, e)| ParseError::new(name, Some(n), ParseErrorKind::Io(e)))?;
+        let error = |kind| ParseError::new(name, Some(number), kind);
+        let Some((property, value)) = line.split_once(':') else {
+            continue;
+        };
+        let property = property.split(';').next().unwrap_or_default();
+
+        let property = property.to_ascii_uppercase();
+        if event.is_some() && nested > 0 {
+            match property.as_str() {
+                "BEGIN" if value.eq_ignore_ascii_case("VEVENT") => {
+                    return Err(error(ParseErrorKind::UnterminatedEvent));
+                }
+                "BEGIN" => nested += 1,
+                "END" if value.eq_ignore_ascii_case("VEVENT") => {
+                    return Err(error(invalid(&line)));
+                }
+                "END" => nested -= 1,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (property.as_str(), event.as_mut()) {
+            ("BEGIN", Some(_)) if !value.eq_ignore_ascii_case("VEVENT") => nested += 1,
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                event = Some(VEvent::new(number));
+            }
+            ("BEGIN", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                return Err(error(ParseErrorKind::UnterminatedEvent));
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                if let Some(e) = event.take() {
+                    f(e)?;
+                }
+            }
+            ("DTSTART", Some(e)) => {
+                e.start = Some(IcsDateTime::parse(value).ok_or_else(|| error(invalid(&line)))?);
+            }
+            ("DTEND", Some(e)) => {
+                e.end = Some(IcsDateTime::parse(value).ok_or_else(|| error(invalid(&line)))?);
+            }
+            ("DURATION", Some(e)) => {
+                e.duration = Some(parse_duration(value).ok_or_else(|| error(invalid(&line)))?);
+            }
+            ("TRANSP", Some(e)) if value.eq_ignore_ascii_case("TRANSPARENT") => e.skip = true,
+            ("STATUS", Some(e)) if value.eq_ignore_ascii_case("CANCELLED") => e.skip = true,
+            _ => {}
+        }
+    }
+
+    match event {
+        Some(e) => Err(ParseError::new(
+            name,
+            Some(e.line),
+            ParseErrorKind::UnterminatedEvent,
+        )),
+        None => Ok(()),
+    }
+}
+
+fn invalid(line: &str) -> ParseErrorKind {
+    ParseErrorKind::InvalidProperty(line.to_string())
+}
+
+// ricompone le righe spezzate (quelle che iniziano con uno spazio o un tab
+// continuano la precedente) tenendo il numero della prima riga
+fn unfold(input: impl BufRead) -> impl Iterator<Item = Result<(usize, String), (usize, String)>> {
+    let mut lines = input.lines().enumerate().peekable();
+    std::iter::from_fn(move || {
+        let (i, line) = lines.next()?;
+        let mut line = match line {
+            Ok(line) => line,
+            Err(e) => return Some(Err((i + 1, e.to_string()))),
+        };
+        while let Some((_, Ok(next))) = lines.peek() {
+            if !next.starts_with([' ', '\t']) {
+                break;
+            }
+            line.truncate(line.trim_end_matches('\r').len());
+            line.push_str(&next[1..]);
+            lines.next();
+        }
+        line.truncate(line.trim_end_matches('\r').len());
+        Some(Ok((i + 1, line)))
+    })
+}
+
+// data (AAAAMMGG) e, se non è un evento di tutto il giorno, l'orario
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct IcsDateTime {
+    date: u32,
+    time: Option<TimeOfDay>,
+}
+
+impl IcsDateTime {
+    // 20240105 oppure 20240105T093000, eventualmente con la Z finale
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        let (date, time) = match value.split_once('T') {
+            Some((date, time)) => (date, Some(time.strip_suffix('Z').unwrap_or(time))),
+            None => (value, None),
+        };
+        if date.len() != 8 || !digits(date) {
+            return None;
+        }
+
+        let time = match time {
+            None => None,
+            Some(time) if time.len() == 6 && digits(time) => {
+                let hours = time[..2].parse().ok()?;
+                let minutes = time[2..4].parse().ok()?;
+                Some(TimeOfDay::new(hours, minutes)?)
+            }
+            Some(_) => return None,
+        };
+        Some(IcsDateTime {
+            date: date.parse().ok()?,
+            time,
+        })
+    }
+
+    fn to_date(self) -> Result<Date, DateError> {
+        let (year, month, day) = (self.date / 10000, self.date / 100 % 100, self.date % 100);
+        Date::new(year as i32, month, day)
+            .ok_or_else(|| DateError::OutOfRange(format!("{:04}-{:02}-{:02}", year, month, day)))
+    }
+}
+
+// durata in minuti nel formato P[nW][nD][T[nH][nM][nS]], i secondi si scartano
+fn parse_duration(value: &str) -> Option<u32> {
+    let mut rest = value.trim().strip_prefix('+').unwrap_or(value.trim());
+    rest = rest.strip_prefix('P')?;
+    let (mut minutes, mut seconds, mut in_time, mut units) = (0u32, 0u32, false, 0);
+    let mut number: Option<u32> = None;
+    for c in rest.chars() {
+        match (c, number) {
+            ('T', None) if !in_time => in_time = true,
+            ('0'..='9', _) => {
+                let digit = c.to_digit(10)?;
+                number = Some(number.unwrap_or(0).checked_mul(10)?.checked_add(digit)?);
+            }
+            (unit, Some(n)) => {
+                let (scale, unit_in_time) = match unit {
+                    'W' => (7 * u32::from(MINUTES_PER_DAY), false),
+                    'D' => (u32::from(MINUTES_PER_DAY), false),
+                    'H' => (60, true),
+                    'M' => (1, true),
+                    'S' => (0, true),
+                    _ => return None,
+                };
+                if unit_in_time != in_time {
+                    return None;
+                }
+                if unit == 'S' {
+                    seconds = seconds.checked_add(n)?;
+                }
+                minutes = minutes.checked_add(n.checked_mul(scale)?)?;
+                number = None;
+                units += 1;
+            }
+            _ => return None,
+        }
+    }
+    if number.is_some() || units == 0 || rest.ends_with('T') {
+        return None;
+    }
+    minutes.checked_add(seconds / 60)
+}
+
+struct VEvent {
+    // riga del BEGIN:VEVENT
+    line: usize,
+    start: Option<IcsDateTime>,
+    end: Option<IcsDateTime>,
+    duration: Option<u32>,
+    skip: bool,
+}
+
+impl VEvent {
+    fn new(line: usize) -> Self {
+        VEvent {
+            line,
+            start: None,
+            end: None,
+            duration: None,
+            skip: false,
+        }
+    }
+
+    // intervallo occupato nel giorno d'inizio, None se l'evento non occupa tempo
+    fn interval(self, name: &str) -> Result<Option<Interval>, ParseError> {
+        let error = |kind| ParseError::new(name, Some(self.line), kind);
+        let start = self
+            .start
+            .ok_or_else(|| error(ParseErrorKind::MissingProperty("DTSTART")))?;
+        let start_time = start.time.unwrap_or(TimeOfDay::MIDNIGHT);
+
+        let end = match (self.end, self.duration) {
+            (Some(end), _) if end.date > start.date => TimeOfDay::END_OF_DAY,
+            (Some(end), _) => end.time.unwrap_or(TimeOfDay::END_OF_DAY),
+            (None, Some(duration)) => u16::try_from(duration)
+                .ok()
+                .and_then(|d| start_time.add_minutes(d))
+                .unwrap_or(TimeOfDay::END_OF_DAY),
+            // senza fine un evento di tutto il giorno dura un giorno, gli altri sono istantanei
+            (None, None) if start.time.is_none() => TimeOfDay::END_OF_DAY,
+            (None, None) => start_time,
+        };
+        if self.skip {
+            return Ok(None);
+        }
+        // una fine in un giorno precedente è lo stesso errore di `dated`
+        if let Some(before) = self.end.filter(|end| end.date < start.date) {
+            let kind = match (start.to_date(), before.to_date()) {
+                (Ok(start_date), Ok(end_date)) => DateError::EmptyEvent(
+                    crate::DateTime::new(start_date, start_time),
+                    crate::DateTime::new(end_date, before.time.unwrap_or(TimeOfDay::MIDNIGHT)),
+                ),
+                (Err(e), _) | (_, Err(e)) => e,
+            };
+            return Err(error(ParseErrorKind::Date(kind)));
+        }
+        if end == start_time {
+            return Ok(None);
+        }
+        Interval::new(start_time, end)
+            .map(Some)
+            .map_err(|e| error(ParseErrorKind::Time(e)))
+    }
+
+    // evento con le sue date, None se l'evento non occupa tempo
+    fn dated(self, name: &str) -> Result<Option<crate::Event>, ParseError> {
+        let error = |kind| ParseError::new(name, Some(self.line), kind);
+        let start = self
+            .start
+            .ok_or_else(|| error(ParseErrorKind::MissingProperty("DTSTART")))?;
+        let start_date = start
+            .to_date()
+            .map_err(|e| error(ParseErrorKind::Date(e)))?;
+        let start_time = start.time.unwrap_or(TimeOfDay::MIDNIGHT);
+        let begin = crate::DateTime::new(start_date, start_time);
+
+        let end = match (self.end, self.duration) {
+            (Some(end), _) => {
+                let date = end.to_date().map_err(|e| error(ParseErrorKind::Date(e)))?;
+                // come in `interval`, una fine senza orario nello stesso giorno è 24:00
+                let time = match end.time {
+                    Some(time) => time,
+                    None if date == start_date => TimeOfDay::END_OF_DAY,
+                    None => TimeOfDay::MIDNIGHT,
+                };
+                crate::DateTime::new(date, time)
+            }
+            (None, Some(duration)) => {
+                let minutes = u64::from(start_time.as_minutes()) + u64::from(duration);
+                let per_day = u64::from(MINUTES_PER_DAY);
+                let time = TimeOfDay::from_minutes((minutes % per_day) as u16)
+                    .unwrap_or(TimeOfDay::MIDNIGHT);
+                crate::DateTime::new(start_date.add_days((minutes / per_day) as i32), time)
+            }
+            (None, None) if start.time.is_none() => {
+                crate::DateTime::new(start_date, TimeOfDay::END_OF_DAY)
+            }
+            (None, None) => begin,
+        };
+        if self.skip || end == begin {
+            return Ok(None);
+        }
+        crate::Event::new(begin, end)
+            .map(Some)
+            .map_err(|e| error(ParseErrorKind::Date(e)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        import_ics, parse_calendar, parse_ics, parse_ics_events, ParseError, ParseErrorKind,
+    };
+    use crate::{Agenda, Calendar, Date, DateError, Interval, TimeError};
+
+    fn show(calendar: &Calendar) -> Vec<String> {
+        calendar.schedule().iter().map(|i| i.to_string()).collect()
+    }
+
+    fn error(text: &str) -> ParseError {
+        parse_calendar("cal.txt", text.as_bytes()).unwrap_err()
+    }
+
+    #[test]
+    fn text_format() {
+        let text = "# orario di lavoro\n08:00\n20:00\n\n10:00  # riunione\n11:30\n14:00-16:00\n  9:00 9:30\n";
+        let calendar = parse_calendar("cal.txt", text.as_bytes()).unwrap();
+        assert_eq!(calendar.bounds().to_string(), "08:00-20:00");
+        assert_eq!(
+            show(&calendar),
+            ["09:00-09:30", "10:00-11:30", "14:00-16:00"]
+        );
+    }
+
+    #[test]
+    fn text_errors() {
+        let e = error("08:00\n20:00\n10:00\n1O:30\n");
+        assert_eq!(e.line, Some(4));
+        assert_eq!(
+            e.kind,
+            ParseErrorKind::Time(TimeError::InvalidFormat("1O:30".to_string()))
+        );
+        assert_eq!(
+            e.to_string(),
+            "cal.txt:4: invalid time \"1O:30\", expected HH:MM"
+        );
+
+        let e = error("08:00\n20:00\n\n12:00\n11:00\n");
+        assert_eq!(e.line, Some(5));
+        assert!(matches!(
+            e.kind,
+            ParseErrorKind::Time(TimeError::EmptyInterval(..))
+        ));
+        assert_eq!(error("08:00\n20:00\n12:00 # da sola\n").line, Some(3));
+        assert_eq!(error("08:00\n").kind, ParseErrorKind::MissingBounds);
+        assert_eq!(
+            error("# vuoto\n\n"),
+            ParseError {
+                file: "cal.txt".to_string(),
+                line: None,
+                kind: ParseErrorKind::MissingBounds,
+            }
+        );
+        assert_eq!(error("08:00-09:00-10:00\n").line, Some(1));
+    }
+
+    #[test]
+    fn ics_events() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nSUMMARY:Riunione\r\n\
+            DTSTART;TZID=Europe/Rome:20240105T093000\r\nDTEND;TZID=Europe/Rome:2024010\r\n 5T104500\r\n\
+            END:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART:20240105T140000Z\r\nDURATION:PT1H30M\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nDTSTART:20240105T220000\r\nDTEND:20240106T010000\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nDTSTART:20240105T120000\r\nDTEND:20240105T130000\r\nTRANSP:TRANSPARENT\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nDTSTART:20240105T080000\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let calendar = parse_ics("cal.ics", ics.as_bytes()).unwrap();
+        assert_eq!(calendar.bounds(), Interval::WHOLE_DAY);
+        assert_eq!(
+            show(&calendar),
+            ["09:30-10:45", "14:00-15:30", "22:00-24:00"]
+        );
+
+        // gli eventi si aggiungono a un calendario già letto dal formato testuale
+        let mut calendar = parse_calendar("cal.txt", "08:00-18:00\n".as_bytes()).unwrap();
+        let all_day = "BEGIN:VEVENT\nDTSTART;VALUE=DATE:20240105\nEND:VEVENT\n";
+        assert_eq!(
+            import_ics(&mut calendar, "day.ics", all_day.as_bytes()),
+            Ok(1)
+        );
+        assert!(calendar.free_slots(1).is_empty());
+    }
+
+    #[test]
+    fn ics_alarms_are_not_part_of_the_event() {
+        let alarm =
+            "BEGIN:VALARM\nACTION:DISPLAY\nTRIGGER:-PT30M\nREPEAT:2\nDURATION:PT15M\nEND:VALARM\n";
+        let ics = format!(
+            "BEGIN:VEVENT\nDTSTART:20240105T090000\n{}DTEND:20240105T100000\nEND:VEVENT\n\
+             BEGIN:VEVENT\nDTSTART:20240105T140000\n{}END:VEVENT\n",
+            alarm, alarm
+        );
+        let calendar = parse_ics("cal.ics", ics.as_bytes()).unwrap();
+        assert_eq!(show(&calendar), ["09:00-10:00"]);
+        assert_eq!(
+            parse_ics_events("cal.ics", ics.as_bytes()).unwrap().len(),
+            1
+        );
+
+        let unclosed = "BEGIN:VEVENT\nDTSTART:20240105T090000\nBEGIN:VALARM\nEND:VEVENT\n";
+        let e = parse_ics("cal.ics", unclosed.as_bytes()).unwrap_err();
+        assert_eq!(e.line, Some(4));
+        assert!(matches!(e.kind, ParseErrorKind::InvalidProperty(_)));
+    }
+
+    #[test]
+    fn ics_dated_events() {
+        let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART:20240105T093000\nDTEND:20240105T104500\nEND:VEVENT\n\
+            BEGIN:VEVENT\nDTSTART:20240106T220000\nDTEND:20240107T010000\nEND:VEVENT\n\
+            BEGIN:VEVENT\nDTSTART;VALUE=DATE:20240108\nEND:VEVENT\n\
+            BEGIN:VEVENT\nDTSTART;VALUE=DATE:20240109\nDTEND;VALUE=DATE:20240111\nEND:VEVENT\n\
+            BEGIN:VEVENT\nDTSTART:20240111T230000\nDURATION:P1DT2H\nEND:VEVENT\n\
+            BEGIN:VEVENT\nDTSTART:20240105T120000\nDTEND:20240105T130000\nSTATUS:CANCELLED\nEND:VEVENT\n\
+            BEGIN:VEVENT\nDTSTART:20240105T080000\nEND:VEVENT\nEND:VCALENDAR\n";
+        let events = parse_ics_events("cal.ics", ics.as_bytes()).unwrap();
+        assert_eq!(events.len(), 5);
+
+        let mut agenda = Agenda::new(Interval::WHOLE_DAY);
+        for event in events {
+            agenda.add(event);
+        }
+        let first: Date = "2024-01-04".parse().unwrap();
+        let days: Vec<String> = (0..10)
+            .map(|k| first.add_days(k))
+            .map(|day| format!("{} {}", day, show(&agenda.day(day)).join(" ")))
+            .collect();
+        assert_eq!(
+            days,
+            [
+                "2024-01-04 ",
+                "2024-01-05 09:30-10:45",
+                "2024-01-06 22:00-24:00",
+                "2024-01-07 00:00-01:00",
+                "2024-01-08 00:00-24:00",
+                "2024-01-09 00:00-24:00",
+                "2024-01-10 00:00-24:00",
+                "2024-01-11 23:00-24:00",
+                "2024-01-12 00:00-24:00",
+                "2024-01-13 00:00-01:00",
+            ]
+        );
+
+        let error = |ics: &str| parse_ics_events("cal.ics", ics.as_bytes()).unwrap_err();
+        let e = error("\nBEGIN:VEVENT\nDTSTART:20230229T100000\nEND:VEVENT\n");
+        assert_eq!(e.line, Some(2));
+        assert_eq!(
+            e.kind,
+            ParseErrorKind::Date(DateError::OutOfRange("2023-02-29".to_string()))
+        );
+        // i due importatori rifiutano allo stesso modo una fine nel giorno prima
+        let reversed = "BEGIN:VEVENT\nDTSTART:20240105T100000\nDTEND:20240104T110000\nEND:VEVENT\n";
+        let e = error(reversed);
+        assert!(matches!(
+            e.kind,
+            ParseErrorKind::Date(DateError::EmptyEvent(..))
+        ));
+        assert_eq!(parse_ics("cal.ics", reversed.as_bytes()), Err(e));
+    }
+
+    #[test]
+    fn ics_errors() {
+        let error = |ics: &str| parse_ics("cal.ics", ics.as_bytes()).unwrap_err();
+        let e = error("BEGIN:VEVENT\nDTSTART:20240105T250000\nEND:VEVENT\n");
+        assert_eq!(e.line, Some(2));
+        assert!(matches!(e.kind, ParseErrorKind::InvalidProperty(_)));
+        assert_eq!(
+            error("BEGIN:VEVENT\nDURATION:PT1H\nEND:VEVENT\n").kind,
+            ParseErrorKind::MissingProperty("DTSTART")
+        );
+        assert_eq!(
+            error("\nBEGIN:VEVENT\nDTSTART:20240105T100000\n").line,
+            Some(2)
+        );
+        assert!(matches!(
+            error("BEGIN:VEVENT\nDTSTART:20240105T100000\nDTEND:20240105T090000\nEND:VEVENT\n")
+                .kind,
+            ParseErrorKind::Time(TimeError::EmptyInterval(..))
+        ));
+        for duration in ["1H", "PT", "P1H", "PT1D", "PT5", "-PT1H"] {
+            let ics = format!(
+                "BEGIN:VEVENT\nDTSTART:20240105T100000\nDURATION:{}\nEND:VEVENT\n",
+                duration
+            );
+            assert_eq!(error(&ics).line, Some(3), "{}", duration);
+        }
+    }
+}
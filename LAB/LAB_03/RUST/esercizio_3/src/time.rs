@@ -0,0 +1,168 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+pub const MINUTES_PER_DAY: u16 = 24 * 60;
+
+// orario del giorno con la precisione del minuto; 24:00 indica la fine della giornata
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeOfDay(u16);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TimeError {
+    InvalidFormat(String),
+    OutOfRange(String),
+    EmptyInterval(TimeOfDay, TimeOfDay),
+}
+
+impl Display for TimeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeError::InvalidFormat(s) => write!(f, "invalid time {:?}, expected HH:MM", s),
+            TimeError::OutOfRange(s) => write!(f, "time {} is out of range", s),
+            TimeError::EmptyInterval(start, end) => {
+                write!(f, "interval {}-{} does not end after it starts", start, end)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TimeError {}
+
+impl TimeOfDay {
+    pub const MIDNIGHT: TimeOfDay = TimeOfDay(0);
+    pub const END_OF_DAY: TimeOfDay = TimeOfDay(MINUTES_PER_DAY);
+
+    pub fn new(hours: u16, minutes: u16) -> Option<Self> {
+        if minutes >= 60 {
+            return None;
+        }
+        Self::from_minutes(hours.checked_mul(60)?.checked_add(minutes)?)
+    }
+
+    pub fn from_minutes(minutes: u16) -> Option<Self> {
+        (minutes <= MINUTES_PER_DAY).then_some(TimeOfDay(minutes))
+    }
+
+    pub fn as_minutes(self) -> u16 {
+        self.0
+    }
+
+    pub fn hours(self) -> u16 {
+        self.0 / 60
+    }
+
+    pub fn minutes(self) -> u16 {
+        self.0 % 60
+    }
+
+    // None se si supera la fine della giornata
+    pub fn add_minutes(self, minutes: u16) -> Option<Self> {
+        Self::from_minutes(self.0.checked_add(minutes)?)
+    }
+}
+
+impl Display for TimeOfDay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}", self.hours(), self.minutes())
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = TimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TimeError::InvalidFormat(s.to_string());
+        let (hours, minutes) = s.trim().split_once(':').ok_or_else(invalid)?;
+        let digits =
+            |x: &str| !x.is_empty() && x.len() <= 2 && x.bytes().all(|b| b.is_ascii_digit());
+        if !digits(hours) || minutes.len() != 2 || !digits(minutes) {
+            return Err(invalid());
+        }
+
+        let hours = hours.parse().map_err(|_| invalid())?;
+        let minutes = minutes.parse().map_err(|_| invalid())?;
+        TimeOfDay::new(hours, minutes).ok_or_else(|| TimeError::OutOfRange(s.to_string()))
+    }
+}
+
+// intervallo [start, end) di una giornata, mai vuoto
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    start: TimeOfDay,
+    end: TimeOfDay,
+}
+
+impl Interval {
+    pub const WHOLE_DAY: Interval = Interval {
+        start: TimeOfDay::MIDNIGHT,
+        end: TimeOfDay::END_OF_DAY,
+    };
+
+    pub fn new(start: TimeOfDay, end: TimeOfDay) -> Result<Self, TimeError> {
+        if start >= end {
+            return Err(TimeError::EmptyInterval(start, end));
+        }
+        Ok(Interval { start, end })
+    }
+
+    pub fn start(&self) -> TimeOfDay {
+        self.start
+    }
+
+    pub fn end(&self) -> TimeOfDay {
+        self.end
+    }
+
+    // durata in minuti
+    pub fn duration(&self) -> u16 {
+        self.end.0 - self.start.0
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end)).ok()
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Interval, TimeError, TimeOfDay};
+
+    #[test]
+    fn parse_and_display() {
+        let t: TimeOfDay = "9:05".parse().unwrap();
+        assert_eq!((t.hours(), t.minutes(), t.as_minutes()), (9, 5, 545));
+        assert_eq!(t.to_string(), "09:05");
+        assert_eq!("24:00".parse(), Ok(TimeOfDay::END_OF_DAY));
+        assert_eq!(
+            "24:01".parse::<TimeOfDay>(),
+            Err(TimeError::OutOfRange("24:01".to_string()))
+        );
+        for bad in ["", "9", "9:5", "09:60", "ab:cd", "-1:00", "10:00:00"] {
+            assert!(bad.parse::<TimeOfDay>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn intervals() {
+        let t = |s: &str| s.parse::<TimeOfDay>().unwrap();
+        let a = Interval::new(t("10:00"), t("11:30")).unwrap();
+        let b = Interval::new(t("11:00"), t("12:00")).unwrap();
+        assert_eq!(a.duration(), 90);
+        assert!(a.overlaps(&b));
+        assert_eq!(a.intersection(&b).unwrap().to_string(), "11:00-11:30");
+        assert_eq!(
+            Interval::new(t("12:00"), t("12:00")),
+            Err(TimeError::EmptyInterval(t("12:00"), t("12:00")))
+        );
+    }
+}
@@ -1,6 +1,6 @@
 /// `InputCellId` is a unique identifier for an input cell.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct InputCellId();
+pub struct InputCellId(usize);
 /// `ComputeCellId` is a unique identifier for a compute cell.
 /// Values of type `InputCellId` and `ComputeCellId` should not be mutually assignable,
 /// demonstrated by the following tests:
@@ -16,9 +16,9 @@ pub struct InputCellId();
 /// let compute: react::InputCellId = r.create_compute(&[react::CellId::Input(input)], |_| 222).unwrap();
 /// ```
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct ComputeCellId();
+pub struct ComputeCellId(usize);
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct CallbackId();
+pub struct CallbackId(usize);
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CellId {
@@ -32,21 +32,45 @@ pub enum RemoveCallbackError {
     NonexistentCallback,
 }
 
-pub struct Reactor<T> {
-    // Just so that the compiler doesn't complain about an unused type parameter.
-    // You probably want to delete this field.
-    dummy: ::std::marker::PhantomData<T>,
+type ComputeFn<'a, T> = Box<dyn Fn(&[T]) -> T + 'a>;
+type Callback<'a, T> = Box<dyn FnMut(T) + 'a>;
+
+struct ComputeCell<'a, T> {
+    dependencies: Vec<CellId>,
+    compute: ComputeFn<'a, T>,
+    value: T,
+    callbacks: Vec<(CallbackId, Callback<'a, T>)>,
+}
+
+// gli id sono gli indici nei vettori: le celle non si possono rimuovere, e una cella
+// calcolata viene sempre dopo le sue dipendenze, quindi l'ordine di creazione è
+// già un ordine topologico
+pub struct Reactor<'a, T> {
+    inputs: Vec<T>,
+    computes: Vec<ComputeCell<'a, T>>,
+    next_callback: usize,
+}
+
+impl<T: Copy + PartialEq> Default for Reactor<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 // You are guaranteed that Reactor will only be tested against types that are Copy + PartialEq.
-impl<T: Copy + PartialEq> Reactor<T> {
+impl<'a, T: Copy + PartialEq> Reactor<'a, T> {
     pub fn new() -> Self {
-        unimplemented!()
+        Reactor {
+            inputs: Vec::new(),
+            computes: Vec::new(),
+            next_callback: 0,
+        }
     }
 
     // Creates an input cell with the specified initial value, returning its ID.
-    pub fn create_input(&mut self, _initial: T) -> InputCellId {
-        unimplemented!()
+    pub fn create_input(&mut self, initial: T) -> InputCellId {
+        self.inputs.push(initial);
+        InputCellId(self.inputs.len() - 1)
     }
 
     // Creates a compute cell with the specified dependencies and compute function.
@@ -62,12 +86,27 @@ impl<T: Copy + PartialEq> Reactor<T> {
     // Notice that there is no way to *remove* a cell.
     // This means that you may assume, without checking, that if the dependencies exist at creation
     // time they will continue to exist as long as the Reactor exists.
-    pub fn create_compute<F: Fn(&[T]) -> T>(
+    pub fn create_compute<F: Fn(&[T]) -> T + 'a>(
         &mut self,
-        _dependencies: &[CellId],
-        _compute_func: F,
+        dependencies: &[CellId],
+        compute_func: F,
     ) -> Result<ComputeCellId, CellId> {
-        unimplemented!()
+        let args = self.values(dependencies)?;
+        self.computes.push(ComputeCell {
+            dependencies: dependencies.to_vec(),
+            value: compute_func(&args),
+            compute: Box::new(compute_func),
+            callbacks: Vec::new(),
+        });
+        Ok(ComputeCellId(self.computes.len() - 1))
+    }
+
+    // valori delle dipendenze nell'ordine dato, Err con la prima che non esiste
+    fn values(&self, dependencies: &[CellId]) -> Result<Vec<T>, CellId> {
+        dependencies
+            .iter()
+            .map(|&id| self.value(id).ok_or(id))
+            .collect()
     }
 
     // Retrieves the current value of the cell, or None if the cell does not exist.
@@ -78,7 +117,10 @@ impl<T: Copy + PartialEq> Reactor<T> {
     // It turns out this introduces a significant amount of extra complexity to this exercise.
     // We chose not to cover this here, since this exercise is probably enough work as-is.
     pub fn value(&self, id: CellId) -> Option<T> {
-        unimplemented!("Get the value of the cell whose id is {id:?}")
+        match id {
+            CellId::Input(InputCellId(index)) => self.inputs.get(index).copied(),
+            CellId::Compute(ComputeCellId(index)) => self.computes.get(index).map(|c| c.value),
+        }
     }
 
     // Sets the value of the specified input cell.
@@ -89,8 +131,46 @@ impl<T: Copy + PartialEq> Reactor<T> {
     // a `set_value(&mut self, new_value: T)` method on `Cell`.
     //
     // As before, that turned out to add too much extra complexity.
-    pub fn set_value(&mut self, _id: InputCellId, _new_value: T) -> bool {
-        unimplemented!()
+    pub fn set_value(&mut self, id: InputCellId, new_value: T) -> bool {
+        let Some(value) = self.inputs.get_mut(id.0) else {
+            return false;
+        };
+        if *value == new_value {
+            return true;
+        }
+        *value = new_value;
+
+        // ogni cella si ricalcola al più una volta, solo se è cambiata una dipendenza,
+        // quindi `changed` dice se il valore finale è diverso da quello di prima
+        let mut changed = vec![false; self.computes.len()];
+        for index in 0..self.computes.len() {
+            let cell = &self.computes[index];
+            let dirty = cell
+                .dependencies
+                .iter()
+                .any(|dependency| match *dependency {
+                    CellId::Input(input) => input == id,
+                    CellId::Compute(ComputeCellId(other)) => changed[other],
+                });
+            if !dirty {
+                continue;
+            }
+            let Ok(args) = self.values(&cell.dependencies) else {
+                continue;
+            };
+            let new_value = (cell.compute)(&args);
+            let cell = &mut self.computes[index];
+            changed[index] = cell.value != new_value;
+            cell.value = new_value;
+        }
+
+        // le callback partono solo a propagazione finita, con i valori finali
+        for (cell, _) in self.computes.iter_mut().zip(changed).filter(|(_, c)| *c) {
+            for (_, callback) in &mut cell.callbacks {
+                callback(cell.value);
+            }
+        }
+        true
     }
 
     // Adds a callback to the specified compute cell.
@@ -105,12 +185,16 @@ impl<T: Copy + PartialEq> Reactor<T> {
     // * Exactly once if the compute cell's value changed as a result of the set_value call.
     //   The value passed to the callback should be the final value of the compute cell after the
     //   set_value call.
-    pub fn add_callback<F: FnMut(T)>(
+    pub fn add_callback<F: FnMut(T) + 'a>(
         &mut self,
-        _id: ComputeCellId,
-        _callback: F,
+        id: ComputeCellId,
+        callback: F,
     ) -> Option<CallbackId> {
-        unimplemented!()
+        let cell = self.computes.get_mut(id.0)?;
+        let callback_id = CallbackId(self.next_callback);
+        self.next_callback += 1;
+        cell.callbacks.push((callback_id, Box::new(callback)));
+        Some(callback_id)
     }
 
     // Removes the specified callback, using an ID returned from add_callback.
@@ -123,8 +207,16 @@ impl<T: Copy + PartialEq> Reactor<T> {
         cell: ComputeCellId,
         callback: CallbackId,
     ) -> Result<(), RemoveCallbackError> {
-        unimplemented!(
-            "Remove the callback identified by the CallbackId {callback:?} from the cell {cell:?}"
-        )
+        let cell = self
+            .computes
+            .get_mut(cell.0)
+            .ok_or(RemoveCallbackError::NonexistentCell)?;
+        let pos = cell
+            .callbacks
+            .iter()
+            .position(|(id, _)| *id == callback)
+            .ok_or(RemoveCallbackError::NonexistentCallback)?;
+        drop(cell.callbacks.remove(pos));
+        Ok(())
     }
 }
@@ -8,7 +8,6 @@ fn input_cells_have_a_value() {
 }
 
 #[test]
-#[ignore]
 fn an_input_cells_value_can_be_set() {
     let mut reactor = Reactor::new();
     let input = reactor.create_input(4);
@@ -17,7 +16,6 @@ fn an_input_cells_value_can_be_set() {
 }
 
 #[test]
-#[ignore]
 fn error_setting_a_nonexistent_input_cell() {
     let mut dummy_reactor = Reactor::new();
     let input = dummy_reactor.create_input(1);
@@ -25,7 +23,6 @@ fn error_setting_a_nonexistent_input_cell() {
 }
 
 #[test]
-#[ignore]
 fn compute_cells_calculate_initial_value() {
     let mut reactor = Reactor::new();
     let input = reactor.create_input(1);
@@ -36,7 +33,6 @@ fn compute_cells_calculate_initial_value() {
 }
 
 #[test]
-#[ignore]
 fn compute_cells_take_inputs_in_the_right_order() {
     let mut reactor = Reactor::new();
     let one = reactor.create_input(1);
@@ -50,7 +46,6 @@ fn compute_cells_take_inputs_in_the_right_order() {
 }
 
 #[test]
-#[ignore]
 fn error_creating_compute_cell_if_input_doesnt_exist() {
     let mut dummy_reactor = Reactor::new();
     let input = dummy_reactor.create_input(1);
@@ -61,7 +56,6 @@ fn error_creating_compute_cell_if_input_doesnt_exist() {
 }
 
 #[test]
-#[ignore]
 fn do_not_break_cell_if_creating_compute_cell_with_valid_and_invalid_input() {
     let mut dummy_reactor = Reactor::new();
     let _ = dummy_reactor.create_input(1);
@@ -77,7 +71,6 @@ fn do_not_break_cell_if_creating_compute_cell_with_valid_and_invalid_input() {
 }
 
 #[test]
-#[ignore]
 fn compute_cells_update_value_when_dependencies_are_changed() {
     let mut reactor = Reactor::new();
     let input = reactor.create_input(1);
@@ -90,7 +83,6 @@ fn compute_cells_update_value_when_dependencies_are_changed() {
 }
 
 #[test]
-#[ignore]
 fn compute_cells_can_depend_on_other_compute_cells() {
     let mut reactor = Reactor::new();
     let input = reactor.create_input(1);
@@ -161,7 +153,6 @@ impl CallbackRecorder {
 }
 
 #[test]
-#[ignore]
 fn compute_cells_fire_callbacks() {
     let cb = CallbackRecorder::new();
     let mut reactor = Reactor::new();
@@ -177,7 +168,6 @@ fn compute_cells_fire_callbacks() {
 }
 
 #[test]
-#[ignore]
 fn error_adding_callback_to_nonexistent_cell() {
     let mut dummy_reactor = Reactor::new();
     let input = dummy_reactor.create_input(1);
@@ -191,7 +181,6 @@ fn error_adding_callback_to_nonexistent_cell() {
 }
 
 #[test]
-#[ignore]
 fn error_removing_callback_from_nonexisting_cell() {
     let mut dummy_reactor = Reactor::new();
     let dummy_input = dummy_reactor.create_input(1);
@@ -215,7 +204,6 @@ fn error_removing_callback_from_nonexisting_cell() {
 }
 
 #[test]
-#[ignore]
 fn callbacks_only_fire_on_change() {
     let cb = CallbackRecorder::new();
     let mut reactor = Reactor::new();
@@ -237,7 +225,6 @@ fn callbacks_only_fire_on_change() {
 }
 
 #[test]
-#[ignore]
 fn callbacks_can_be_called_multiple_times() {
     let cb = CallbackRecorder::new();
     let mut reactor = Reactor::new();
@@ -256,7 +243,6 @@ fn callbacks_can_be_called_multiple_times() {
 }
 
 #[test]
-#[ignore]
 fn callbacks_can_be_called_from_multiple_cells() {
     let cb1 = CallbackRecorder::new();
     let cb2 = CallbackRecorder::new();
@@ -281,7 +267,6 @@ fn callbacks_can_be_called_from_multiple_cells() {
 }
 
 #[test]
-#[ignore]
 fn callbacks_can_be_added_and_removed() {
     let cb1 = CallbackRecorder::new();
     let cb2 = CallbackRecorder::new();
@@ -316,7 +301,6 @@ fn callbacks_can_be_added_and_removed() {
 }
 
 #[test]
-#[ignore]
 fn removing_a_callback_multiple_times_doesnt_interfere_with_other_callbacks() {
     let cb1 = CallbackRecorder::new();
     let cb2 = CallbackRecorder::new();
@@ -347,7 +331,6 @@ fn removing_a_callback_multiple_times_doesnt_interfere_with_other_callbacks() {
 }
 
 #[test]
-#[ignore]
 fn callbacks_should_only_be_called_once_even_if_multiple_dependencies_change() {
     let cb = CallbackRecorder::new();
     let mut reactor = Reactor::new();
@@ -375,7 +358,6 @@ fn callbacks_should_only_be_called_once_even_if_multiple_dependencies_change() {
 }
 
 #[test]
-#[ignore]
 fn callbacks_should_not_be_called_if_dependencies_change_but_output_value_doesnt_change() {
     let cb = CallbackRecorder::new();
     let mut reactor = Reactor::new();
@@ -402,7 +384,6 @@ fn callbacks_should_not_be_called_if_dependencies_change_but_output_value_doesnt
 }
 
 #[test]
-#[ignore]
 fn test_adder_with_boolean_values() {
     // This is a digital logic circuit called an adder:
     // https://en.wikipedia.org/wiki/Adder_(electronics)
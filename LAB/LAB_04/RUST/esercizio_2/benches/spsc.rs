@@ -0,0 +1,74 @@
+// stesso carico per le due versioni: un produttore inserisce N interi, un
+// consumatore li toglie tutti; si lancia con `cargo bench`
+use esercizio_2::{ring_buffer, CircularBuffer, MAX_LENGTH_TAPE};
+use std::hint::black_box;
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::Instant;
+
+const N: i32 = 200_000;
+const RUNS: u32 = 5;
+
+fn rwlock() {
+    let buf = Arc::new(RwLock::new(CircularBuffer::new()));
+    let buf_producer = buf.clone();
+
+    let producer = thread::spawn(move || {
+        let mut i = 0;
+        while i < N {
+            let mut prod_buf = buf_producer.write().unwrap();
+            if !prod_buf.is_full() {
+                prod_buf.insert(i);
+                i += 1;
+            } else {
+                // come fa push nella versione senza lock, si cede il processore
+                drop(prod_buf);
+                thread::yield_now();
+            }
+        }
+    });
+
+    let mut received = 0;
+    while received < N {
+        let value = buf.write().unwrap().remove();
+        match value {
+            Some(value) => {
+                black_box(value);
+                received += 1;
+            }
+            None => thread::yield_now(),
+        }
+    }
+    producer.join().unwrap();
+}
+
+fn spsc() {
+    let (mut producer, consumer) = ring_buffer(MAX_LENGTH_TAPE);
+    let producer = thread::spawn(move || {
+        for i in 0..N {
+            producer.push(i).unwrap();
+        }
+    });
+
+    for value in consumer {
+        black_box(value);
+    }
+    producer.join().unwrap();
+}
+
+fn bench(name: &str, f: fn()) {
+    f();
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        f();
+    }
+    let per_run = start.elapsed() / RUNS;
+    let ns_op = per_run.as_nanos() as f64 / N as f64;
+    println!("{:<8} {:>10.2?}/run {:>8.1} ns/value", name, per_run, ns_op);
+}
+
+fn main() {
+    println!("{} values, capacity {}", N, MAX_LENGTH_TAPE);
+    bench("rwlock", rwlock);
+    bench("spsc", spsc);
+}
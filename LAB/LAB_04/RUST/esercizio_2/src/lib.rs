@@ -0,0 +1,72 @@
+mod spsc;
+
+pub use spsc::{ring_buffer, Consumer, Producer};
+
+pub const MAX_LENGTH_TAPE: usize = 10;
+
+pub struct CircularBuffer {
+    pub tape: Vec<i32>,
+    ind_r: usize,
+    ind_w: usize,
+    len: usize,
+    max_len: usize,
+}
+
+impl CircularBuffer {
+    pub fn new() -> Self {
+        Self::with_capacity(MAX_LENGTH_TAPE)
+    }
+
+    pub fn with_capacity(max_len: usize) -> Self {
+        CircularBuffer {
+            tape: vec![-1; max_len],
+            ind_r: 0,
+            ind_w: 0,
+            len: 0,
+            max_len,
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == self.max_len
+    }
+
+    pub fn insert(&mut self, value: i32) {
+        if self.len < self.max_len {
+            self.tape[self.ind_w] = value;
+            self.ind_w = (self.ind_w + 1) % self.max_len;
+            self.len += 1;
+        }
+    }
+
+    // toglie il valore più vecchio, se c'è
+    pub fn remove(&mut self) -> Option<i32> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let value = self.tape[self.ind_r];
+        self.tape[self.ind_r] = -1;
+        self.ind_r = (self.ind_r + 1) % self.max_len;
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn read(&mut self) -> String {
+        let mut result = String::new();
+
+        while self.tape[self.ind_r] != -1 {
+            result.push_str(format!("{} ", self.tape[self.ind_r]).as_str());
+            self.tape[self.ind_r] = -1;
+            self.ind_r = (self.ind_r + 1) % self.max_len;
+            self.len -= 1;
+        }
+        result
+    }
+}
+
+impl Default for CircularBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
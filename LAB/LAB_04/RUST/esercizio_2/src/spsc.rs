@@ -0,0 +1,276 @@
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+// tiene head e tail su linee di cache diverse, così produttore e consumatore
+// non si contendono la stessa linea a ogni operazione
+#[repr(align(64))]
+struct Padded(AtomicUsize);
+
+// buffer circolare senza lock per un solo produttore e un solo consumatore:
+// tail viene scritto solo dal produttore e head solo dal consumatore; i due
+// contatori crescono sempre e la cella è data dal resto della divisione
+struct Ring<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    head: Padded,
+    tail: Padded,
+    // scritto con Release da chi viene distrutto per primo: chi lo legge con Acquire
+    // vede anche l'ultimo tail (o head) che l'altro lato ha scritto
+    closed: AtomicBool,
+}
+
+// le celle tra head e tail sono accessibili solo al consumatore, le altre solo
+// al produttore, quindi basta che T si possa spostare tra thread
+unsafe impl<T: Send> Sync for Ring<T> {}
+
+impl<T> Ring<T> {
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+}
+
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        let tail = *self.tail.0.get_mut();
+        let mut head = *self.head.0.get_mut();
+        while head != tail {
+            let slot = &mut self.slots[head % self.slots.len()];
+            unsafe { slot.get_mut().assume_init_drop() };
+            head = head.wrapping_add(1);
+        }
+    }
+}
+
+pub struct Producer<T> {
+    ring: Arc<Ring<T>>,
+}
+
+pub struct Consumer<T> {
+    ring: Arc<Ring<T>>,
+}
+
+// una capacità nulla viene portata a 1
+pub fn ring_buffer<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    let slots = (0..capacity.max(1))
+        .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+        .collect();
+    let ring = Arc::new(Ring {
+        slots,
+        head: Padded(AtomicUsize::new(0)),
+        tail: Padded(AtomicUsize::new(0)),
+        closed: AtomicBool::new(false),
+    });
+    (Producer { ring: ring.clone() }, Consumer { ring })
+}
+
+// attesa attiva breve, poi si cede il processore agli altri thread
+fn backoff(step: &mut u32) {
+    if *step < 8 {
+        std::hint::spin_loop();
+    } else {
+        thread::yield_now();
+    }
+    *step = step.saturating_add(1);
+}
+
+impl<T> Producer<T> {
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+
+    // il consumatore è stato distrutto: nessuno leggerà più i valori
+    pub fn is_disconnected(&self) -> bool {
+        self.ring.closed.load(Ordering::Acquire)
+    }
+
+    // inserisce senza attendere; se il buffer è pieno restituisce il valore
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        let ring = &*self.ring;
+        let tail = ring.tail.0.load(Ordering::Relaxed);
+        let head = ring.head.0.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) == ring.capacity() {
+            return Err(value);
+        }
+
+        let slot = &ring.slots[tail % ring.capacity()];
+        unsafe { (*slot.get()).write(value) };
+        ring.tail.0.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    // attende che si liberi una cella; fallisce solo se il consumatore non c'è più
+    pub fn push(&mut self, mut value: T) -> Result<(), T> {
+        let mut step = 0;
+        loop {
+            match self.try_push(value) {
+                Ok(()) => return Ok(()),
+                Err(v) if self.is_disconnected() => return Err(v),
+                Err(v) => value = v,
+            }
+            backoff(&mut step);
+        }
+    }
+}
+
+impl<T> Consumer<T> {
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+
+    // il produttore è stato distrutto: non arriveranno altri valori
+    pub fn is_disconnected(&self) -> bool {
+        self.ring.closed.load(Ordering::Acquire)
+    }
+
+    pub fn len(&self) -> usize {
+        let head = self.ring.head.0.load(Ordering::Relaxed);
+        let tail = self.ring.tail.0.load(Ordering::Acquire);
+        tail.wrapping_sub(head)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // toglie il valore più vecchio senza attendere
+    pub fn try_pop(&mut self) -> Option<T> {
+        let ring = &*self.ring;
+        let head = ring.head.0.load(Ordering::Relaxed);
+        let tail = ring.tail.0.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+
+        let slot = &ring.slots[head % ring.capacity()];
+        let value = unsafe { (*slot.get()).assume_init_read() };
+        ring.head.0.store(head.wrapping_add(1), Ordering::Release);
+        Some(value)
+    }
+
+    // attende un valore; None solo quando il produttore non c'è più e il buffer è vuoto
+    pub fn pop(&mut self) -> Option<T> {
+        let mut step = 0;
+        loop {
+            if let Some(value) = self.try_pop() {
+                return Some(value);
+            }
+            if self.is_disconnected() {
+                // il produttore può aver scritto un ultimo valore prima di sparire
+                return self.try_pop();
+            }
+            backoff(&mut step);
+        }
+    }
+}
+
+impl<T> Drop for Producer<T> {
+    fn drop(&mut self) {
+        self.ring.closed.store(true, Ordering::Release);
+    }
+}
+
+impl<T> Drop for Consumer<T> {
+    fn drop(&mut self) {
+        self.ring.closed.store(true, Ordering::Release);
+    }
+}
+
+impl<T> Iterator for Consumer<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.pop()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ring_buffer;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn try_push_and_pop() {
+        let (mut producer, mut consumer) = ring_buffer(2);
+        assert_eq!(consumer.try_pop(), None);
+        assert_eq!(producer.try_push(1), Ok(()));
+        assert_eq!(producer.try_push(2), Ok(()));
+        assert_eq!(producer.try_push(3), Err(3));
+        assert_eq!(consumer.len(), 2);
+        assert_eq!(consumer.try_pop(), Some(1));
+        assert_eq!(producer.try_push(3), Ok(()));
+        assert_eq!(consumer.try_pop(), Some(2));
+        assert_eq!(consumer.try_pop(), Some(3));
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn threads_keep_order() {
+        const N: usize = 100_000;
+        let (mut producer, consumer) = ring_buffer(16);
+        let handle = thread::spawn(move || {
+            for i in 0..N {
+                producer.push(i).unwrap();
+            }
+        });
+        // l'iteratore termina quando il produttore finisce e il buffer è vuoto
+        assert!(consumer.eq(0..N));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn disconnection() {
+        let (mut producer, consumer) = ring_buffer(1);
+        producer.push("a").unwrap();
+        drop(consumer);
+        assert!(producer.is_disconnected());
+        assert_eq!(producer.push("b"), Err("b"));
+
+        let (mut producer, mut consumer) = ring_buffer(4);
+        producer.push(7).unwrap();
+        drop(producer);
+        assert_eq!(consumer.pop(), Some(7));
+        assert_eq!(consumer.pop(), None);
+    }
+
+    #[test]
+    fn last_values_survive_disconnection() {
+        // il produttore sparisce subito dopo l'ultima scrittura, molte volte
+        for round in 0..1000 {
+            let (mut producer, consumer) = ring_buffer(4);
+            let handle = thread::spawn(move || {
+                for i in 0..3 {
+                    producer.push(i).unwrap();
+                }
+            });
+            assert_eq!(consumer.collect::<Vec<_>>(), [0, 1, 2], "round {}", round);
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn remaining_values_are_dropped() {
+        struct Counted(Arc<AtomicUsize>);
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let drops = Arc::new(AtomicUsize::new(0));
+        let (mut producer, mut consumer) = ring_buffer(3);
+        for _ in 0..5 {
+            let value = Counted(drops.clone());
+            if producer.try_push(value).is_err() {
+                consumer.try_pop();
+            }
+        }
+        // un valore rifiutato e uno tolto dal consumatore, tre ancora nel buffer
+        assert_eq!(drops.load(Ordering::SeqCst), 2);
+        drop((producer, consumer));
+        assert_eq!(drops.load(Ordering::SeqCst), 5);
+    }
+}